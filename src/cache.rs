@@ -0,0 +1,379 @@
+//! On-disk cache of Exercism API responses.
+//!
+//! When the cache is enabled, the API clients are pointed at a local [`HttpCache`] proxy that
+//! forwards requests to the Exercism API. Responses listing solutions or their files are stored
+//! in the cache directory, keyed by their URL and API token:
+//!
+//! ```text
+//! cache/
+//! └── http/
+//!     ├── <key>.json      Response status, content type, validators and time of last validation
+//!     └── <key>.body      Raw response body
+//! ```
+//!
+//! Cached responses are used as-is while they are younger than the configured TTL. Afterwards,
+//! they are revalidated using the `ETag` and `Last-Modified` headers returned by the API: if the
+//! API answers `304 Not Modified`, the cached response is used and its age is reset.
+//!
+//! Other responses (like file contents) are streamed back as they are received, so that
+//! downloads are not buffered by the proxy and are throttled end-to-end by `--limit-rate`.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use hyper::header::{
+    ACCEPT, AUTHORIZATION, CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use hyper::{Body, Method, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::fs;
+use tracing::{debug, trace, warn};
+
+use crate::command::args::CacheArgs;
+use crate::http_session::upstream_base_url;
+use crate::local_server::LocalServer;
+use crate::Result;
+
+/// Name of the environment variable that can be used to override the cache directory.
+pub const CACHE_DIR_ENV_VAR: &str = "EXSB_CACHE_DIR";
+
+/// Returns the directory where `exsb` stores cached data.
+///
+/// This is the [`CACHE_DIR_ENV_VAR`] directory if set, otherwise an `exsb` directory in the
+/// user's cache directory (if it can be determined).
+pub fn cache_dir() -> Option<PathBuf> {
+    std::env::var_os(CACHE_DIR_ENV_VAR)
+        .map(PathBuf::from)
+        .or_else(|| dirs::cache_dir().map(|cache_dir| cache_dir.join(env!("CARGO_PKG_NAME"))))
+}
+
+/// Local proxy caching responses of the Exercism API.
+///
+/// The proxy is stopped when dropped.
+#[derive(Debug)]
+pub struct HttpCache {
+    server: LocalServer,
+}
+
+impl HttpCache {
+    /// Starts a caching proxy on a random local port, unless disabled by `args`.
+    ///
+    /// Requests are forwarded to `api_base_url` if specified; otherwise, they are forwarded to the
+    /// v1 or v2 Exercism API depending on the endpoint (like when recording an HTTP session).
+    pub async fn start(
+        args: &CacheArgs,
+        http_client: reqwest::Client,
+        api_base_url: Option<&str>,
+    ) -> Result<Option<Self>> {
+        if args.no_cache {
+            return Ok(None);
+        }
+        let Some(cache_path) = cache_dir().map(|cache_dir| cache_dir.join("http")) else {
+            warn!("Failed to determine cache directory; API responses will not be cached");
+            return Ok(None);
+        };
+        trace!(cache_path = %cache_path.display());
+
+        let proxy = Arc::new(CachingProxy {
+            cache_path,
+            ttl: args.cache_ttl,
+            http_client,
+            api_base_url: api_base_url.map(|url| url.trim_end_matches('/').to_string()),
+        });
+
+        let server = LocalServer::start(None, "caching proxy", move |request| {
+            proxy.clone().forward(request)
+        })?;
+
+        Ok(Some(Self { server }))
+    }
+
+    /// Returns the base URI of the proxy.
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    content_type: Option<String>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    validated_at: u64,
+}
+
+#[derive(Debug)]
+struct CachingProxy {
+    cache_path: PathBuf,
+    ttl: Duration,
+    http_client: reqwest::Client,
+    api_base_url: Option<String>,
+}
+
+impl CachingProxy {
+    async fn forward(self: Arc<Self>, request: hyper::Request<Body>) -> Response<Body> {
+        self.respond(request).await.unwrap_or_else(|error| {
+            warn!("Failed to forward request: {error:#}");
+            Response::builder()
+                .status(StatusCode::BAD_GATEWAY)
+                .body(Body::from(format!("{error:#}")))
+                .expect("error response should be valid")
+        })
+    }
+
+    async fn respond(&self, request: hyper::Request<Body>) -> Result<Response<Body>> {
+        let path = request.uri().path().to_string();
+        let base_url = match &self.api_base_url {
+            Some(api_base_url) => api_base_url.as_str(),
+            None => upstream_base_url(&path),
+        };
+        let url = match request.uri().query() {
+            Some(query) => format!("{base_url}{path}?{query}"),
+            None => format!("{base_url}{path}"),
+        };
+
+        let key = (request.method() == Method::GET && is_cacheable(&path)).then(|| {
+            let authorization = request
+                .headers()
+                .get(AUTHORIZATION)
+                .map(|value| value.as_bytes())
+                .unwrap_or_default();
+            cache_key(&url, authorization)
+        });
+        let cached = match &key {
+            Some(key) => self.read_entry(key).await,
+            None => None,
+        };
+
+        if let Some((entry, body)) = &cached {
+            if now().saturating_sub(entry.validated_at) < self.ttl.as_secs() {
+                debug!(%url, "Using cached response");
+                return cached_response(entry, body.clone());
+            }
+        }
+
+        let mut upstream_request = self.http_client.request(request.method().clone(), &url);
+        for header in [AUTHORIZATION, ACCEPT] {
+            if let Some(value) = request.headers().get(&header) {
+                upstream_request = upstream_request.header(header, value);
+            }
+        }
+        if let Some((entry, _)) = &cached {
+            if let Some(etag) = &entry.etag {
+                upstream_request = upstream_request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                upstream_request = upstream_request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+        let request_body = hyper::body::to_bytes(request.into_body())
+            .await
+            .with_context(|| format!("failed to read request body for {url}"))?;
+        let upstream_response = upstream_request
+            .body(request_body)
+            .send()
+            .await
+            .with_context(|| format!("failed to forward request to {url}"))?;
+
+        let status = upstream_response.status();
+        let header = |name| {
+            upstream_response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let entry = CacheEntry {
+            content_type: header(CONTENT_TYPE),
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            validated_at: now(),
+        };
+
+        match (status, key, cached) {
+            (StatusCode::NOT_MODIFIED, Some(key), Some((mut cached_entry, body))) => {
+                debug!(%url, "Cached response is still valid");
+                cached_entry.validated_at = entry.validated_at;
+                self.write_metadata(&key, &cached_entry).await;
+                cached_response(&cached_entry, body)
+            },
+            (StatusCode::OK, Some(key), _) => {
+                let body = upstream_response
+                    .bytes()
+                    .await
+                    .with_context(|| format!("failed to read response body from {url}"))?
+                    .to_vec();
+                trace!(%url, "Caching response");
+                self.write_entry(&key, &entry, &body).await;
+
+                uncached_response(status, &entry, Body::from(body), &url)
+            },
+            (status, _, _) => {
+                let body = Body::wrap_stream(upstream_response.bytes_stream());
+                uncached_response(status, &entry, body, &url)
+            },
+        }
+    }
+
+    async fn read_entry(&self, key: &str) -> Option<(CacheEntry, Vec<u8>)> {
+        let metadata_path = self.cache_path.join(format!("{key}.json"));
+        let body_path = metadata_path.with_extension("body");
+
+        let entry = fs::read(&metadata_path)
+            .await
+            .ok()
+            .and_then(|metadata| serde_json::from_slice(&metadata).ok())?;
+        let body = fs::read(&body_path).await.ok()?;
+        Some((entry, body))
+    }
+
+    /// Writes a cache entry. Errors are only logged, since the response can be used anyway.
+    async fn write_entry(&self, key: &str, entry: &CacheEntry, body: &[u8]) {
+        let body_path = self.cache_path.join(format!("{key}.body"));
+        let result = async {
+            fs::create_dir_all(&self.cache_path)
+                .await
+                .with_context(|| {
+                    format!("failed to create cache directory {}", self.cache_path.display())
+                })?;
+            fs::write(&body_path, body)
+                .await
+                .with_context(|| format!("failed to write {}", body_path.display()))
+        }
+        .await;
+
+        match result {
+            Ok(()) => self.write_metadata(key, entry).await,
+            Err(error) => warn!("Failed to cache API response: {error:#}"),
+        }
+    }
+
+    async fn write_metadata(&self, key: &str, entry: &CacheEntry) {
+        let metadata_path = self.cache_path.join(format!("{key}.json"));
+        let result = async {
+            let metadata = serde_json::to_vec_pretty(entry)
+                .with_context(|| "failed to serialize cache entry")?;
+            fs::write(&metadata_path, metadata)
+                .await
+                .with_context(|| format!("failed to write {}", metadata_path.display()))
+        }
+        .await;
+
+        if let Err(error) = result {
+            warn!("Failed to cache API response: {error:#}");
+        }
+    }
+}
+
+/// Determines if responses for the given endpoint should be cached.
+///
+/// Only listings are cached: solutions (v2 API), a solution's files (v1 API) and tracks.
+/// File contents can be large, and the token validation endpoint must always be checked.
+fn is_cacheable(path: &str) -> bool {
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+
+    matches!(segments.as_slice(), ["solutions"] | ["solutions", _] | ["tracks", ..])
+}
+
+/// Returns the key of the cache entry for a request, so that accounts don't share entries.
+fn cache_key(url: &str, authorization: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(url.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(authorization);
+    format!("{:x}", hasher.finalize())
+}
+
+fn cached_response(entry: &CacheEntry, body: Vec<u8>) -> Result<Response<Body>> {
+    let mut response = Response::builder().status(StatusCode::OK);
+    if let Some(content_type) = &entry.content_type {
+        response = response.header(CONTENT_TYPE, content_type);
+    }
+    response
+        .body(Body::from(body))
+        .with_context(|| "failed to build cached response")
+}
+
+fn uncached_response(
+    status: StatusCode,
+    entry: &CacheEntry,
+    body: Body,
+    url: &str,
+) -> Result<Response<Body>> {
+    let mut response = Response::builder().status(status);
+    if let Some(content_type) = &entry.content_type {
+        response = response.header(CONTENT_TYPE, content_type);
+    }
+    response
+        .body(body)
+        .with_context(|| format!("failed to build response for {url}"))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_cacheable() {
+        assert!(is_cacheable("/solutions"));
+        assert!(is_cacheable("/solutions/1234"));
+        assert!(is_cacheable("/tracks/rust/exercises"));
+        assert!(!is_cacheable("/solutions/1234/files/src/lib.rs"));
+        assert!(!is_cacheable("/validate_token"));
+    }
+
+    #[tokio::test]
+    async fn test_stream_uncached_response() {
+        use hyper::body::HttpBody;
+
+        // Upstream server sending the first chunk of a file, then waiting.
+        let (mut sender, body) = Body::channel();
+        let body = Arc::new(std::sync::Mutex::new(Some(body)));
+        let upstream = LocalServer::start(None, "upstream server", move |_| {
+            let body = body.lock().unwrap().take().unwrap_or_default();
+            async move { Response::new(body) }
+        })
+        .unwrap();
+        sender.send_data("first chunk".into()).await.unwrap();
+
+        let proxy = CachingProxy {
+            cache_path: PathBuf::from("unused"),
+            ttl: Duration::from_secs(60),
+            http_client: reqwest::Client::new(),
+            api_base_url: Some(upstream.uri()),
+        };
+        let request = hyper::Request::get("/solutions/1234/files/big.txt")
+            .body(Body::empty())
+            .unwrap();
+        let first_chunk = async {
+            let mut response = proxy.respond(request).await.unwrap();
+            response.body_mut().data().await
+        };
+
+        let chunk = tokio::time::timeout(Duration::from_secs(5), first_chunk)
+            .await
+            .expect("first chunk should be streamed before the upstream response is complete");
+        assert_eq!(b"first chunk".as_slice(), chunk.unwrap().unwrap().as_ref());
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let key = cache_key("https://exercism.org/api/v2/solutions?page=1", b"Bearer alice");
+
+        assert_eq!(64, key.len());
+        assert_ne!(key, cache_key("https://exercism.org/api/v2/solutions?page=1", b"Bearer bob"));
+        assert_ne!(key, cache_key("https://exercism.org/api/v2/solutions?page=2", b"Bearer alice"));
+    }
+}
@@ -1,14 +1,66 @@
 //! Definition of supported CLI commands.
 
+#[macro_use]
+mod detail;
+
+pub mod args;
+#[cfg(feature = "keyring")]
+#[cfg_attr(any(nightly_rustc, docsrs), doc(cfg(feature = "keyring")))]
+pub mod auth;
 pub mod backup;
+pub mod completions;
+pub mod daemon;
+pub mod dev;
+pub mod diff;
+pub mod export_html;
+pub mod man;
+pub mod prune;
+pub mod restore;
+pub mod search;
+pub mod serve;
+pub mod stats;
+pub mod watch;
+
+use std::path::Path;
 
 use clap::Subcommand;
 
+#[cfg(feature = "keyring")]
+use crate::command::auth::args::AuthArgs;
+#[cfg(feature = "keyring")]
+use crate::command::auth::AuthCommand;
 use crate::command::backup::args::BackupArgs;
 use crate::command::backup::BackupCommand;
+use crate::command::completions::args::CompletionsArgs;
+use crate::command::completions::CompletionsCommand;
+use crate::command::daemon::args::DaemonArgs;
+use crate::command::daemon::DaemonCommand;
+use crate::command::dev::args::DevArgs;
+use crate::command::dev::DevCommand;
+use crate::command::diff::args::DiffArgs;
+use crate::command::diff::DiffCommand;
+use crate::command::export_html::args::ExportHtmlArgs;
+use crate::command::export_html::ExportHtmlCommand;
+use crate::command::man::args::ManArgs;
+use crate::command::man::ManCommand;
+use crate::command::prune::args::PruneArgs;
+use crate::command::prune::PruneCommand;
+use crate::command::restore::args::RestoreArgs;
+use crate::command::restore::RestoreCommand;
+use crate::command::search::args::SearchArgs;
+use crate::command::search::SearchCommand;
+use crate::command::serve::args::ServeArgs;
+use crate::command::serve::ServeCommand;
+use crate::command::stats::args::StatsArgs;
+use crate::command::stats::StatsCommand;
+use crate::command::watch::args::WatchArgs;
+use crate::command::watch::WatchCommand;
+use crate::http_session::HttpSession;
 use crate::Result;
 
 /// Possible commands supported by our CLI application.
+// Commands are only created once when parsing arguments, so their size doesn't matter much.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Subcommand)]
 pub enum Command {
     /// Download Exercism.org solutions for backup
@@ -19,23 +71,197 @@ pub enum Command {
     ///
     /// If an exercise has had multiple iterations submitted, the latest iteration is always downloaded.
     ///
-    /// To download solutions, an Exercism API token is needed. If not specified via the --token option,
-    /// by default, the API token configured for the local installation of the Exercism CLI application
-    /// will be used. The command does not require the Exercism CLI to work, but if it's not installed,
-    /// then the API token will have to be specified (see --token).
+    /// To download solutions, an Exercism API token is needed. It can be specified via the --token or
+    /// --token-file options, or via the EXERCISM_TOKEN environment variable. Otherwise, the token
+    /// stored in the system keyring (see the auth command) will be used, if any. Finally, the API
+    /// token configured for the local installation of the Exercism CLI application will be used. The
+    /// command does not require the Exercism CLI to work, but if it's not installed, then the API token
+    /// will have to be specified using one of the methods above. To backup multiple accounts in a
+    /// single run, specify named tokens via --accounts-file instead.
+    ///
+    /// Downloaded files can optionally be encrypted using a key file or a passphrase (see
+    /// --encryption-key-file and --encryption-passphrase). Use the restore command to decrypt them.
+    ///
+    /// When re-downloading solutions with --force, previous copies can be kept as older iterations
+    /// with --keep-iterations.
+    ///
+    /// SHA-256 checksums of downloaded files are stored with each solution. Use --verify-existing
+    /// to check existing solutions against them and download corrupt files again.
+    ///
+    /// By default, all solutions are attempted and failures are reported at the end; use
+    /// --fail-fast or --max-failures to stop earlier.
+    ///
+    /// Lists of solutions and files returned by the Exercism API are cached on disk, and are only
+    /// fetched again once revalidated with Exercism after --cache-ttl; use --no-cache to always
+    /// fetch them.
+    ///
+    /// To help reproduce problems, exchanges with the Exercism API can be recorded with --record
+    /// and replayed later (without network access) with --replay.
     Backup(BackupArgs),
+
+    /// Run backups periodically
+    ///
+    /// Accepts the same arguments as the backup command, and runs a backup immediately, then at
+    /// the specified --interval. Alternatively, backups can be run according to a cron expression
+    /// (see --schedule). Since existing solutions are skipped (unless --force is used), each backup
+    /// only downloads new solutions. Credentials are only resolved once, when the daemon starts.
+    ///
+    /// The status of backup runs (number of runs and failures, last result, next run, etc.) is
+    /// written to a JSON status file, which is kept between executions of the daemon. It can
+    /// also be served on a local HTTP endpoint for monitoring (see --status-port). Logs can be
+    /// written to a file with --log-file.
+    Daemon(DaemonArgs),
+
+    /// Back up new iterations as soon as they are submitted
+    ///
+    /// Accepts the same arguments as the backup command, and regularly checks for solutions whose
+    /// latest iteration changed since they were backed up; only those are downloaded again. Checks
+    /// start at --min-interval, and slow down up to --max-interval while no new iterations are
+    /// submitted. Each check usually only fetches the first page of solutions (newest first).
+    ///
+    /// The files of changed solutions are overwritten, but other files in their directories are
+    /// kept; use --force to replace their directories entirely, or --force --keep-iterations to
+    /// keep previous iterations.
+    ///
+    /// Changes are detected using the solution metadata stored in the backup, so solutions backed
+    /// up by a version of exsb that did not store it are downloaded again on the first check.
+    Watch(WatchArgs),
+
+    /// Decrypt an encrypted backup
+    ///
+    /// Backups created with the --encryption-key-file or --encryption-passphrase options contain
+    /// encrypted files. This command decrypts all files of such a backup to another directory,
+    /// using the same key file or passphrase. Files that are not encrypted are copied as-is.
+    ///
+    /// Use --check to verify that every file can be decrypted and authenticated without
+    /// writing anything.
+    Restore(RestoreArgs),
+
+    /// Remove backed-up solutions that would not be backed up anymore
+    ///
+    /// Compares the backup with the current solutions of the Exercism account: exercise and
+    /// track directories that do not correspond to a solution (for example because it was
+    /// deleted, or the exercise was renamed or deprecated) are removed. Solutions that do not
+    /// match the track, exercise or status filters (see the backup command) are removed too.
+    ///
+    /// Use --dry-run to see what would be pruned, and --quarantine to move orphaned directories
+    /// elsewhere instead of deleting them. When backing up multiple accounts, prune each
+    /// account's subdirectory separately.
+    Prune(PruneArgs),
+
+    /// Summarize Exercism progress from a backup
+    ///
+    /// Computes per-track solution counts by status, iteration counts, lines of code, a timeline
+    /// of completed exercises by month and the largest solutions. Statistics are computed from a
+    /// backup directory, or from the Exercism API with --from-api (in which case lines of code
+    /// are those reported by Exercism). API responses are cached like for the backup command.
+    ///
+    /// Status and iterations are only known for solutions backed up by a version of exsb that
+    /// stores solution metadata. Use --format to export statistics as JSON, or one CSV record per
+    /// solution.
+    Stats(StatsArgs),
+
+    /// Export a backup as a static HTML site
+    ///
+    /// The generated site contains an index of tracks, the list of exercises of each track with
+    /// their status, and a page for each exercise with its source files. Pages do not load any
+    /// external resources; source files are tagged with their language, so a syntax highlighter
+    /// can be added to the site. The status, number of iterations and mentoring status of
+    /// solutions are only displayed for solutions backed up by a version of exsb that stores
+    /// solution metadata.
+    ExportHtml(ExportHtmlArgs),
+
+    /// Browse and search a backup in a web browser
+    ///
+    /// Starts a local web server (only reachable from this computer) serving the same pages as
+    /// the export-html command, as well as a full-text search across all backed-up files. The
+    /// backup is read when the server starts; restart it to see solutions backed up since then.
+    Serve(ServeArgs),
+
+    /// Search backed-up solutions using a regular expression
+    ///
+    /// Matches are grouped by solution and shown with context lines, like grep. Solutions can be
+    /// filtered by track, exercise and status (like for the backup command); status is only
+    /// known for solutions backed up by a version of exsb that stores solution metadata.
+    ///
+    /// By default, only the latest iteration of each solution is searched; use --all-iterations
+    /// to also search previous iterations kept with `backup --keep-iterations`.
+    Search(SearchArgs),
+
+    /// Show differences between a backed-up solution and Exercism, or between iterations
+    ///
+    /// By default, the backed-up files of the solution are compared with the files of its latest
+    /// iteration on Exercism, as a unified diff (local files are prefixed with a/, Exercism files
+    /// with b/). This shows what would change if the solution was downloaded again with
+    /// `backup --force`. Accessing Exercism requires an API token (see the backup command).
+    ///
+    /// With --iterations, two iterations of the backed-up solution are compared instead; previous
+    /// iterations are only available if they were kept with `backup --keep-iterations`.
+    Diff(DiffArgs),
+
+    /// Generate shell completions
+    ///
+    /// Prints a completion script for the given shell to standard output. For bash and fish, the
+    /// script also completes the values of --track and --exercise, using the solutions seen by
+    /// previous commands that listed solutions from Exercism (like backup).
+    ///
+    /// For example, to enable completions in bash, add `source <(exsb completions bash)` to your
+    /// .bashrc file.
+    Completions(CompletionsArgs),
+
+    /// Generate man pages
+    ///
+    /// By default, the main man page is printed to standard output. Use --output to write man
+    /// pages for exsb and all its commands to a directory.
+    Man(ManArgs),
+
+    /// Manage the Exercism API token stored in the system keyring
+    ///
+    /// Storing the token in the system keyring avoids having to pass it on the command line, where
+    /// it could leak into shell history or process listings.
+    #[cfg(feature = "keyring")]
+    #[cfg_attr(any(nightly_rustc, docsrs), doc(cfg(feature = "keyring")))]
+    Auth(AuthArgs),
+
+    /// Tools used when developing exsb
+    #[command(hide = true)]
+    Dev(DevArgs),
 }
 
 impl Command {
+    /// Returns the file where logs should be written instead of the standard error, if any.
+    pub fn log_file(&self) -> Option<&Path> {
+        match self {
+            Command::Daemon(args) => args.log_file.as_deref(),
+            _ => None,
+        }
+    }
+
     /// Execute this [`Command`].
     ///
     /// This method is provided explicitly in order to make it `async`.
     pub async fn execute(self) -> Result<()> {
         match self {
             Command::Backup(args) => {
-                let backup_command = BackupCommand::new(args, None)?;
+                // The session must outlive the backup, since API clients talk to it.
+                let session = HttpSession::start(&args).await?;
+                let backup_command = BackupCommand::with_session(args, session.as_ref())?;
                 BackupCommand::execute(backup_command).await
             },
+            Command::Daemon(args) => DaemonCommand::execute(DaemonCommand::new(args).await?).await,
+            Command::Watch(args) => WatchCommand::new(args)?.execute().await,
+            Command::Restore(args) => RestoreCommand::new(args)?.execute().await,
+            Command::Prune(args) => PruneCommand::new(args).execute().await,
+            Command::Stats(args) => StatsCommand::new(args).execute().await,
+            Command::ExportHtml(args) => ExportHtmlCommand::new(args).execute().await,
+            Command::Serve(args) => ServeCommand::execute(ServeCommand::new(args).await?).await,
+            Command::Search(args) => SearchCommand::new(args).execute().await,
+            Command::Diff(args) => DiffCommand::new(args).execute().await,
+            Command::Completions(args) => CompletionsCommand::new(args).execute().await,
+            Command::Man(args) => ManCommand::new(args).execute().await,
+            #[cfg(feature = "keyring")]
+            Command::Auth(args) => AuthCommand::new(args).execute().await,
+            Command::Dev(args) => DevCommand::new(args).execute().await,
         }
     }
 }
@@ -0,0 +1,253 @@
+//! Command-line arguments shared by multiple [commands](crate::command::Command).
+
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Context;
+use clap::Args;
+use reqwest::{Certificate, Proxy};
+
+use crate::command::backup::args::SolutionStatus;
+use crate::crypto::{Cipher, Secret};
+use crate::Result;
+
+/// Command-line arguments used to specify the Exercism API token.
+///
+/// If neither argument is specified, the token is looked up in the `EXERCISM_TOKEN` environment
+/// variable, then in the system keyring (see `exsb auth login`) and finally in the configuration
+/// of the Exercism CLI, if installed.
+#[derive(Clone, Default, Args)]
+pub struct CredentialsArgs {
+    /// Exercism.org API token; if unspecified, EXERCISM_TOKEN, the stored token or the CLI token will be used
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// File containing the Exercism.org API token
+    #[arg(long, value_name = "FILE", conflicts_with = "token")]
+    pub token_file: Option<PathBuf>,
+}
+
+impl fmt::Debug for CredentialsArgs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CredentialsArgs")
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .field("token_file", &self.token_file)
+            .finish()
+    }
+}
+
+/// Command-line arguments used to configure the HTTP client used to access the Exercism API.
+#[derive(Debug, Clone, Args)]
+pub struct HttpClientArgs {
+    /// Proxy to use for HTTP(S) requests (by default, HTTP_PROXY and HTTPS_PROXY are honored)
+    #[arg(long, value_name = "URL")]
+    pub proxy: Option<String>,
+
+    /// Maximum time to wait for a connection to be established, in seconds
+    #[arg(long, value_name = "SECONDS")]
+    pub connect_timeout: Option<u64>,
+
+    /// Maximum time to wait for data when making a request or downloading a file, in seconds
+    #[arg(long, value_name = "SECONDS")]
+    pub read_timeout: Option<u64>,
+
+    /// Additional root CA certificate(s) to trust, in PEM format (can be used multiple times)
+    #[arg(long, value_name = "FILE")]
+    pub ca_cert: Vec<PathBuf>,
+
+    /// User agent to send with HTTP requests
+    #[arg(long, default_value = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")))]
+    pub user_agent: String,
+}
+
+impl HttpClientArgs {
+    /// Returns the maximum time to wait for data, if specified.
+    pub fn read_timeout(&self) -> Option<Duration> {
+        self.read_timeout.map(Duration::from_secs)
+    }
+
+    pub(crate) fn build_http_client(&self) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder().user_agent(&self.user_agent);
+
+        if let Some(proxy) = &self.proxy {
+            builder = builder
+                .proxy(Proxy::all(proxy).with_context(|| format!("invalid proxy URL: {proxy}"))?);
+        }
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(Duration::from_secs(connect_timeout));
+        }
+        for ca_cert in &self.ca_cert {
+            let pem = std::fs::read(ca_cert).with_context(|| {
+                format!("failed to read CA certificate file {}", ca_cert.display())
+            })?;
+            let certificates = Certificate::from_pem_bundle(&pem).with_context(|| {
+                format!("invalid PEM data in CA certificate file {}", ca_cert.display())
+            })?;
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        builder
+            .build()
+            .with_context(|| "failed to create HTTP client")
+    }
+}
+
+/// Command-line arguments used to configure the on-disk cache of Exercism API responses.
+#[derive(Debug, Clone, Args)]
+pub struct CacheArgs {
+    /// Do not cache listings of solutions and files returned by the Exercism API
+    #[arg(long, default_value_t = false)]
+    pub no_cache: bool,
+
+    /// Time during which cached API responses are used without checking if they changed (e.g. `10m`)
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration, default_value = "10m")]
+    pub cache_ttl: Duration,
+}
+
+/// Command-line arguments used to specify the key used to encrypt or decrypt backed-up files.
+#[derive(Clone, Default, Args)]
+pub struct EncryptionArgs {
+    /// Encrypt/decrypt files using a key derived from the content of this file
+    #[arg(long, value_name = "FILE", conflicts_with = "encryption_passphrase")]
+    pub encryption_key_file: Option<PathBuf>,
+
+    /// Encrypt/decrypt files using a key derived from this passphrase
+    #[arg(long, env = "EXSB_ENCRYPTION_PASSPHRASE", hide_env_values = true)]
+    pub encryption_passphrase: Option<String>,
+}
+
+impl fmt::Debug for EncryptionArgs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Arguments are traced, so make sure not to leak the passphrase.
+        f.debug_struct("EncryptionArgs")
+            .field("encryption_key_file", &self.encryption_key_file)
+            .field(
+                "encryption_passphrase",
+                &self.encryption_passphrase.as_ref().map(|_| "<redacted>"),
+            )
+            .finish()
+    }
+}
+
+impl EncryptionArgs {
+    /// Returns `true` if a key source has been specified.
+    pub fn is_enabled(&self) -> bool {
+        self.encryption_key_file.is_some() || self.encryption_passphrase.is_some()
+    }
+
+    pub(crate) fn cipher(&self) -> Result<Option<Cipher>> {
+        let secret = match (&self.encryption_key_file, &self.encryption_passphrase) {
+            (Some(key_file), _) => Secret::from_key_file(key_file)?,
+            (None, Some(passphrase)) => Secret::Passphrase(passphrase.clone()),
+            (None, None) => return Ok(None),
+        };
+
+        Cipher::new(secret).map(Some)
+    }
+}
+
+/// Command-line arguments used to select solutions by track, exercise and status.
+#[derive(Debug, Clone, Args)]
+pub struct SolutionFilterArgs {
+    /// Only include solutions in the given track(s) (can be used multiple times)
+    #[arg(short, long)]
+    pub track: Vec<String>,
+
+    /// Only include solutions for the given exercise(s) (can be used multiple times)
+    #[arg(short, long)]
+    pub exercise: Vec<String>,
+
+    /// Only include solutions with the given status (or greater)
+    #[arg(short, long, value_enum, default_value_t = SolutionStatus::Submitted)]
+    pub status: SolutionStatus,
+}
+
+impl SolutionFilterArgs {
+    /// Determines if a solution matches these filters.
+    ///
+    /// A `solution_status` of [`None`] (e.g. for solutions that have only been started, or whose
+    /// status is unknown) only matches if all statuses are accepted.
+    pub fn matches(
+        &self,
+        track_name: &str,
+        exercise_name: &str,
+        solution_status: Option<SolutionStatus>,
+    ) -> bool {
+        self.track_matches(track_name) &&
+            self.exercise_matches(exercise_name) &&
+            self.solution_status_matches(solution_status)
+    }
+
+    fn track_matches(&self, track_name: &str) -> bool {
+        self.track.is_empty() || self.track.iter().any(|t| t == track_name)
+    }
+
+    fn exercise_matches(&self, exercise_name: &str) -> bool {
+        self.exercise.is_empty() || self.exercise.iter().any(|e| e == exercise_name)
+    }
+
+    fn solution_status_matches(&self, solution_status: Option<SolutionStatus>) -> bool {
+        self.status == SolutionStatus::Submitted
+            || solution_status.map_or(false, |st| st >= self.status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod http_client_args {
+        use super::*;
+
+        fn http_client_args() -> HttpClientArgs {
+            HttpClientArgs {
+                proxy: None,
+                connect_timeout: Some(10),
+                read_timeout: Some(30),
+                ca_cert: vec![],
+                user_agent: "exsb-tests".into(),
+            }
+        }
+
+        #[test]
+        fn test_build_http_client() {
+            let args = HttpClientArgs {
+                proxy: Some("http://localhost:3128".into()),
+                ..http_client_args()
+            };
+
+            assert!(args.build_http_client().is_ok());
+            assert_eq!(Some(Duration::from_secs(30)), args.read_timeout());
+        }
+
+        #[test]
+        fn test_missing_ca_cert() {
+            let args =
+                HttpClientArgs { ca_cert: vec!["does-not-exist.pem".into()], ..http_client_args() };
+
+            assert!(args.build_http_client().is_err());
+        }
+    }
+
+    mod solution_filter_args {
+        use super::*;
+
+        #[test]
+        fn test_matches() {
+            let args = SolutionFilterArgs {
+                track: vec!["rust".into()],
+                exercise: vec![],
+                status: SolutionStatus::Completed,
+            };
+
+            assert!(args.matches("rust", "poker", Some(SolutionStatus::Published)));
+            assert!(!args.matches("rust", "poker", Some(SolutionStatus::Submitted)));
+            assert!(!args.matches("rust", "poker", None));
+            assert!(!args.matches("elixir", "two-fer", Some(SolutionStatus::Completed)));
+        }
+    }
+}
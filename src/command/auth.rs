@@ -0,0 +1,85 @@
+//! Definition of the [`Auth`](crate::command::Command::Auth) command.
+
+pub mod args;
+
+use anyhow::{anyhow, Context};
+use mini_exercism::api;
+use mini_exercism::core::Credentials;
+use tracing::{info, instrument};
+
+use crate::command::args::HttpClientArgs;
+use crate::command::auth::args::{AuthAction, AuthArgs, LoginArgs, StatusArgs};
+use crate::credentials::{
+    explicit_credentials, keyring_store, resolve_credentials, validate_credentials,
+    CredentialSource,
+};
+use crate::Result;
+
+/// Command wrapper used for the [`Auth`](crate::command::Command::Auth) command.
+#[derive(Debug)]
+pub struct AuthCommand {
+    args: AuthArgs,
+}
+
+impl AuthCommand {
+    /// Creates a new [`AuthCommand`] using the provided [`args`](AuthArgs).
+    pub fn new(args: AuthArgs) -> Self {
+        Self { args }
+    }
+
+    /// Execute the authentication action.
+    #[instrument(skip_all)]
+    pub async fn execute(&self) -> Result<()> {
+        match &self.args.action {
+            AuthAction::Login(args) => Self::login(args).await,
+            AuthAction::Logout => Self::logout(),
+            AuthAction::Status(args) => Self::status(args).await,
+        }
+    }
+
+    async fn login(args: &LoginArgs) -> Result<()> {
+        let (credentials, source) = match explicit_credentials(&args.credentials)? {
+            Some(credentials) => credentials,
+            None => {
+                let token = rpassword::prompt_password("Exercism API token: ")
+                    .with_context(|| "failed to read token from terminal")?;
+                match token.trim() {
+                    "" => return Err(anyhow!("no token specified")),
+                    token => (Credentials::from_api_token(token), CredentialSource::Terminal),
+                }
+            },
+        };
+
+        validate_credentials(&v1_client(&credentials, &args.http)?, &source)
+            .await
+            .with_context(|| "token has not been stored")?;
+
+        keyring_store::store_token(credentials.api_token())?;
+        info!("Exercism API token validated and stored in system keyring");
+        Ok(())
+    }
+
+    fn logout() -> Result<()> {
+        match keyring_store::delete_token()? {
+            true => info!("Exercism API token removed from system keyring"),
+            false => info!("No Exercism API token stored in system keyring"),
+        }
+        Ok(())
+    }
+
+    async fn status(args: &StatusArgs) -> Result<()> {
+        let (credentials, source) = resolve_credentials(&args.credentials)?;
+
+        validate_credentials(&v1_client(&credentials, &args.http)?, &source).await?;
+
+        info!("Exercism API token from {source} is valid");
+        Ok(())
+    }
+}
+
+fn v1_client(credentials: &Credentials, http_args: &HttpClientArgs) -> Result<api::v1::Client> {
+    Ok(api::v1::Client::builder()
+        .http_client(http_args.build_http_client()?)
+        .credentials(credentials.clone())
+        .build())
+}
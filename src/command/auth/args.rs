@@ -0,0 +1,53 @@
+//! Arguments that can be passed to the [`Auth`](crate::command::Command::Auth) command.
+
+use clap::{Args, Subcommand};
+
+use crate::command::args::{CredentialsArgs, HttpClientArgs};
+
+/// Command-line arguments accepted by the [`Auth`](crate::command::Command::Auth) command.
+#[derive(Debug, Clone, Args)]
+pub struct AuthArgs {
+    /// Authentication action to perform.
+    #[command(subcommand)]
+    pub action: AuthAction,
+}
+
+/// Possible actions of the [`Auth`](crate::command::Command::Auth) command.
+#[derive(Debug, Clone, Subcommand)]
+pub enum AuthAction {
+    /// Validate an Exercism API token and store it in the system keyring
+    ///
+    /// The token can be specified via --token, --token-file or the EXERCISM_TOKEN environment
+    /// variable. If none are present, the token will be read from the terminal.
+    Login(LoginArgs),
+
+    /// Remove the Exercism API token stored in the system keyring
+    Logout,
+
+    /// Show which Exercism API token would be used and whether it is valid
+    Status(StatusArgs),
+}
+
+/// Command-line arguments accepted by [`AuthAction::Login`].
+#[derive(Debug, Clone, Args)]
+pub struct LoginArgs {
+    /// Exercism API token to store
+    #[command(flatten)]
+    pub credentials: CredentialsArgs,
+
+    /// Configuration of the HTTP client used to access the Exercism API
+    #[command(flatten)]
+    pub http: HttpClientArgs,
+}
+
+/// Command-line arguments accepted by [`AuthAction::Status`].
+#[derive(Debug, Clone, Args)]
+pub struct StatusArgs {
+    /// Exercism API token to check (if unspecified, checks the token that would be used by other commands)
+    #[command(flatten)]
+    pub credentials: CredentialsArgs,
+
+    /// Configuration of the HTTP client used to access the Exercism API
+    #[command(flatten)]
+    pub http: HttpClientArgs,
+}
@@ -1,30 +1,54 @@
 //! Definition of the [`Backup`](crate::command::Command::Backup) command.
 
 pub mod args;
-#[macro_use]
-mod detail;
+pub(crate) mod account;
 
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::future::Future;
 use std::panic::resume_unwind;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use anyhow::Context;
-use futures::StreamExt;
-use mini_exercism::api;
+use anyhow::{anyhow, Context};
+use futures::{stream, StreamExt};
 use mini_exercism::api::v2::solution::Solution;
 use mini_exercism::api::v2::solutions;
-use mini_exercism::cli::get_cli_credentials;
 use mini_exercism::core::Credentials;
+use sha2::{Digest, Sha256};
+use tokio::sync::mpsc;
+use tokio::time::timeout;
 use tokio::{fs, spawn};
 use tokio::io::{AsyncWriteExt, BufWriter};
-use tracing::{info, instrument, trace, Level, debug, enabled};
+use tracing::{info, instrument, trace, warn, Level, debug, enabled};
 
-use crate::command::backup::args::BackupArgs;
-use crate::download_limiter::DownloadLimiter;
-use crate::task_pool::TaskPool;
+use crate::command::backup::account::{read_accounts_file, Account};
+use crate::command::backup::args::{BackupArgs, ExerciseType};
+use crate::credentials::{
+    explicit_credentials, resolve_credentials, validate_credentials, CredentialSource,
+};
+use crate::crypto::Cipher;
+use crate::download_limiter::{DownloadLimiter, RateLimiter};
+use crate::error::report::ErrorReport;
+use crate::error::ErrorScope;
+use crate::http_session::{HttpSession, REDACTED_TOKEN};
+use crate::known_solutions;
+use crate::known_solutions::KnownSolution;
+use crate::local_backup::{
+    checksums_path, format_checksums, iteration_numbers, iterations_path, read_checksums,
+    list_subdirectories, read_solution_metadata, set_modification_time_from_subdirectories,
+    set_modification_times, sha256_hex, submission_time, Checksums, METADATA_DIR,
+    SOLUTION_METADATA_FILE,
+};
+use crate::local_server::LocalServer;
+use crate::task_pool::{FailurePolicy, TaskPool, TaskPriority, TaskSpawner};
 use crate::Result;
 
+/// Maximum number of solutions fetched from the Exercism API, but not yet being backed up.
+const SOLUTION_QUEUE_SIZE: usize = 64;
+
 /// Command wrapper used for the [`Backup`](crate::command::Command::Backup) command.
 ///
 /// # Notes
@@ -44,32 +68,100 @@ use crate::Result;
 #[derive(Debug)]
 pub struct BackupCommand {
     args: BackupArgs,
-    v1_client: api::v1::Client,
-    v2_client: api::v2::Client,
+    accounts: Vec<Arc<Account>>,
     limiter: DownloadLimiter,
+    rate_limiter: Option<RateLimiter>,
+    read_timeout: Option<Duration>,
+    cipher: Option<Cipher>,
+    exercise_types: tokio::sync::Mutex<HashMap<String, HashMap<String, ExerciseType>>>,
+    known_solutions: Mutex<BTreeSet<KnownSolution>>,
 }
 
 impl BackupCommand {
     /// Creates a new [`BackupCommand`] using the provided [`args`](BackupArgs).
     ///
+    /// If an [`accounts_file`](BackupArgs::accounts_file) is specified, one account is backed
+    /// up per named token found in the file; otherwise, a single account is backed up.
+    ///
     /// The `api_base_url` parameter should only be set to test using a different Exercism local endpoint.
     pub fn new(args: BackupArgs, api_base_url: Option<&str>) -> Result<Arc<Self>> {
-        let http_client = reqwest::Client::builder()
-            .build()
-            .with_context(|| "failed to create HTTP client")?;
-        let credentials = args
-            .token
-            .as_ref()
-            .map(|token| Ok(Credentials::from_api_token(token)))
-            .unwrap_or_else(|| {
-                get_cli_credentials().with_context(|| "failed to get Exercism CLI credentials")
-            })?;
+        let http_client = args.http.build_http_client()?;
+        Self::with_http_client(args, http_client, api_base_url)
+    }
+
+    /// Creates a new [`BackupCommand`] whose API clients talk to the given HTTP session, if any
+    /// (see [`record`](BackupArgs::record), [`replay`](BackupArgs::replay) and [`HttpCache`](crate::cache::HttpCache)).
+    pub(crate) fn with_session(args: BackupArgs, session: Option<&HttpSession>) -> Result<Arc<Self>> {
+        match session {
+            Some(session) => Self::with_http_client(args, LocalServer::http_client()?, Some(&session.uri())),
+            None => {
+                let api_base_url = args.api_base_url.clone();
+                Self::new(args, api_base_url.as_deref())
+            },
+        }
+    }
+
+    fn with_http_client(args: BackupArgs, http_client: reqwest::Client, api_base_url: Option<&str>) -> Result<Arc<Self>> {
+        let accounts = match &args.accounts_file {
+            Some(accounts_file) => read_accounts_file(accounts_file)?
+                .into_iter()
+                .map(|(name, credentials)| {
+                    let credential_source = CredentialSource::AccountsFile {
+                        path: accounts_file.clone(),
+                        account: name.clone(),
+                    };
+                    Account::new(
+                        Some(name),
+                        &http_client,
+                        &credentials,
+                        credential_source,
+                        api_base_url,
+                    )
+                })
+                .map(Arc::new)
+                .collect(),
+            None => {
+                let (credentials, credential_source) = match &args.replay {
+                    // Recorded sessions do not contain the token, so any token will do.
+                    Some(session_path) => explicit_credentials(&args.credentials)?.unwrap_or_else(
+                        || {
+                            (
+                                Credentials::from_api_token(REDACTED_TOKEN),
+                                CredentialSource::Replay(session_path.clone()),
+                            )
+                        },
+                    ),
+                    None => resolve_credentials(&args.credentials)?,
+                };
+
+                vec![Arc::new(Account::new(
+                    None,
+                    &http_client,
+                    &credentials,
+                    credential_source,
+                    api_base_url,
+                ))]
+            },
+        };
 
-        let v1_client = build_client!(api::v1::Client, http_client, credentials, api_base_url);
-        let v2_client = build_client!(api::v2::Client, http_client, credentials, api_base_url);
         let limiter = DownloadLimiter::new(args.max_downloads);
+        let rate_limiter = args.limit_rate.map(|rate| RateLimiter::new(rate.0));
+        let read_timeout = args.http.read_timeout();
+        let cipher = args
+            .encryption
+            .cipher()
+            .with_context(|| "failed to initialize encryption")?;
 
-        Ok(Arc::new(Self { args, v1_client, v2_client, limiter }))
+        Ok(Arc::new(Self {
+            args,
+            accounts,
+            limiter,
+            rate_limiter,
+            read_timeout,
+            cipher,
+            exercise_types: Default::default(),
+            known_solutions: Default::default(),
+        }))
     }
 
     /// Execute the backup operation.
@@ -87,70 +179,358 @@ impl BackupCommand {
         })?;
         trace!(output_path = %output_path.display());
 
-        match spawn(Self::backup_solutions(this.clone(), output_path)).await {
-            Ok(Ok(())) => {
-                info!("Exercism solutions backup complete");
-                Ok(())
-            },
-            Ok(Err(task_error)) => Err(task_error),
+        let backup = Self::backup_accounts(this.clone(), output_path, Self::backup_solutions);
+        let result = match spawn(backup).await {
+            Ok(result) => result,
             Err(join_error) => resume_unwind(join_error.into_panic()),
+        };
+        this.save_known_solutions().await;
+        if let Some(error_report_path) = &this.args.error_report {
+            let report = result
+                .as_ref()
+                .err()
+                .map(ErrorReport::from_error)
+                .unwrap_or_default();
+            if let Err(error) = write_error_report(error_report_path, &report).await {
+                warn!("{error:#}");
+            }
         }
+
+        result?;
+        info!("Exercism solutions backup complete");
+        Ok(())
     }
 
-    #[instrument(skip(this))]
-    async fn backup_solutions(this: Arc<Self>, output_path: PathBuf) -> Result<()> {
-        let mut task_pool = TaskPool::new();
+    /// Backs up each account using `backup_account`, concurrently. Each account is backed up in
+    /// its own subdirectory of `output_path`, unless a single unnamed account is backed up.
+    ///
+    /// Errors are scoped to their account for [reporting](ErrorReport).
+    #[instrument(skip_all)]
+    async fn backup_accounts<F, Fut>(
+        this: Arc<Self>,
+        output_path: PathBuf,
+        backup_account: F,
+    ) -> Result<()>
+    where
+        F: Fn(Arc<Self>, Arc<Account>, PathBuf) -> Fut,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        if let [account] = this.accounts.as_slice() {
+            if account.name.is_none() {
+                let result = backup_account(this.clone(), account.clone(), output_path.clone()).await;
+                return result.and(this.set_directory_times(&output_path, false).await);
+            }
+        }
 
-        let mut page = 1;
-        loop {
-            let (solutions, meta) = this.get_solutions_for_page(page).await?;
+        // --max-failures applies to the solutions of each account; only --fail-fast stops other accounts.
+        let failure_policy = match this.args.fail_fast {
+            true => FailurePolicy::FailFast,
+            false => FailurePolicy::Continue,
+        };
+        let mut task_pool = TaskPool::new().with_failure_policy(failure_policy);
 
+        for account in &this.accounts {
+            let mut account_output_path = output_path.clone();
+            if let Some(name) = &account.name {
+                account_output_path.push(name);
+            }
+
+            let scope = ErrorScope::Account(account.display_name().to_string());
+            let backup = backup_account(this.clone(), account.clone(), account_output_path.clone());
+            let this = this.clone();
+            task_pool.spawn(async move {
+                let result = backup.await;
+                result
+                    .and(this.set_directory_times(&account_output_path, true).await)
+                    .map_err(|error| scope.wrap(error))
+            });
+        }
+
+        task_pool
+            .join(|| "errors detected while backing up accounts")
+            .await
+    }
+
+    #[instrument(skip(this, account), fields(account = account.display_name()))]
+    async fn backup_solutions(this: Arc<Self>, account: Arc<Account>, output_path: PathBuf) -> Result<()> {
+        this.validate_credentials(&account).await?;
+
+        let mut task_pool = TaskPool::bounded(this.args.max_downloads)
+            .with_failure_policy(this.args.failure_policy());
+
+        // Pages are fetched by a separate task (outside the pool, so that it doesn't count as a
+        // solution for the failure policy) and their solutions are sent through a bounded channel.
+        // Since the number of solutions being backed up at once is limited by the pool, page
+        // fetching pauses when downloads can't keep up.
+        let (sender, mut receiver) = mpsc::channel(SOLUTION_QUEUE_SIZE);
+        let fetcher = spawn(Self::fetch_solutions(this.clone(), account.clone(), output_path.clone(), sender));
+
+        while let Some(solution) = task_pool.run_until(receiver.recv()).await {
+            task_pool.ready().await;
+            if task_pool.is_aborted() {
+                break;
+            }
+            Self::spawn_solution_backup(&this, &mut task_pool, &account, &output_path, solution, false);
+        }
+
+        // Dropping the receiver stops the fetcher if the pool was aborted.
+        drop(receiver);
+        match fetcher.await {
+            Ok(Ok(())) => (),
+            Ok(Err(error)) => task_pool.add_error(error),
+            Err(join_error) => resume_unwind(join_error.into_panic()),
+        }
+
+        task_pool
+            .join(|| "errors detected while backing up solutions")
+            .await
+    }
+
+    /// Fetches all pages of solutions of an account and sends those to back up to `sender`.
+    ///
+    /// The first page is fetched alone to determine the number of pages; other pages are then
+    /// fetched concurrently (subject to the [`DownloadLimiter`]), but processed in order.
+    #[instrument(skip_all)]
+    async fn fetch_solutions(
+        this: Arc<Self>,
+        account: Arc<Account>,
+        output_path: PathBuf,
+        sender: mpsc::Sender<Solution>,
+    ) -> Result<()> {
+        let first_page = this.get_solutions_for_page(&account, 1).await?;
+        let total_pages = first_page.1.total_pages;
+        let other_pages = stream::iter(2..=total_pages)
+            .map(|page| this.get_solutions_for_page(&account, page))
+            .buffered(this.args.max_downloads.max(1));
+        let mut pages = stream::once(async { Ok(first_page) })
+            .chain(other_pages)
+            .boxed();
+
+        while let Some((solutions, meta)) = pages.next().await.transpose()? {
+            let page = meta.current_page;
             if solutions.is_empty() {
                 info!("No solutions to backup in page {page}");
+                continue;
+            }
+
+            if this.args.dry_run && enabled!(Level::INFO) {
+                let solutions_list = solutions
+                    .iter()
+                    .map(|solution| format!("{}/{}", solution.track.name, solution.exercise.name))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                info!("Solutions to backup in page {page}: {solutions_list}");
             } else {
-                if this.args.dry_run && enabled!(Level::INFO) {
-                    let solutions_list = solutions
-                        .iter()
-                        .map(|solution| format!("{}/{}", solution.track.name, solution.exercise.name))
-                        .collect::<Vec<_>>()
-                        .join(", ");
-                    info!("Solutions to backup in page {page}: {solutions_list}");
-                } else {
-                    info!("Number of solutions to back in page {page}: {}", solutions.len());
-                }
+                info!("Number of solutions to back in page {page}: {}", solutions.len());
+            }
 
-                // Create track directories right away so that concurrent tasks don't end up trying
-                // to create a directory multiple times.
-                this.create_track_directories(&output_path, &solutions)
-                    .await?;
+            // Create track directories right away so that concurrent tasks don't end up trying
+            // to create a directory multiple times.
+            this.create_track_directories(&output_path, &solutions)
+                .await?;
 
-                if !this.args.dry_run || enabled!(Level::DEBUG) {
-                    for solution in solutions {
-                        task_pool.spawn(Self::backup_solution(
-                            this.clone(),
-                            output_path.clone(),
-                            solution,
-                        ));
+            if !this.args.dry_run || enabled!(Level::DEBUG) {
+                for solution in solutions {
+                    if sender.send(solution).await.is_err() {
+                        // Receiver is gone, so nobody is interested in remaining solutions.
+                        return Ok(());
                     }
                 }
             }
+        }
+
+        Ok(())
+    }
+
+    /// Validates the token of an account, unless it has already been validated.
+    ///
+    /// Credentials are validated up front, otherwise an invalid token would only be reported as
+    /// a failure to fetch the first page of solutions.
+    async fn validate_credentials(&self, account: &Account) -> Result<()> {
+        if account.credentials_validated.load(SeqCst) {
+            return Ok(());
+        }
+
+        {
+            let _permit = self.limiter.get_permit().await;
+            self.with_read_timeout(validate_credentials(
+                &account.v1_client,
+                &account.credential_source,
+            ))
+            .await??;
+        }
+        account.credentials_validated.store(true, SeqCst);
+        Ok(())
+    }
+
+    /// Backs up solutions whose latest iteration changed since they were last backed up.
+    ///
+    /// Solutions are fetched newest first, one page at a time, stopping after the first page that
+    /// contains an unchanged solution; this way, checking for changes usually only requires a single
+    /// request per account. The files of changed solutions are downloaded again, overwriting existing
+    /// ones. Returns the number of solutions that were backed up.
+    #[instrument(skip_all)]
+    pub(crate) async fn backup_changed_solutions(this: Arc<Self>) -> Result<usize> {
+        this.create_output_directory(&this.args.path).await?;
+
+        let output_path = this.args.path.canonicalize().with_context(|| {
+            format!("failed to get absolute path for output directory {}", this.args.path.display())
+        })?;
+
+        let num_changed = Arc::new(AtomicUsize::new(0));
+        let result = {
+            let num_changed = num_changed.clone();
+            Self::backup_accounts(this.clone(), output_path, move |this, account, output_path| {
+                Self::backup_changed_account_solutions(
+                    this,
+                    account,
+                    output_path,
+                    num_changed.clone(),
+                )
+            })
+            .await
+        };
+        this.save_known_solutions().await;
+
+        result?;
+        Ok(num_changed.load(SeqCst))
+    }
+
+    /// Backs up the changed solutions of an account, adding their number to `num_changed`
+    /// (see [`backup_changed_solutions`](Self::backup_changed_solutions)).
+    #[instrument(skip(this, account, num_changed), fields(account = account.display_name()))]
+    async fn backup_changed_account_solutions(
+        this: Arc<Self>,
+        account: Arc<Account>,
+        output_path: PathBuf,
+        num_changed: Arc<AtomicUsize>,
+    ) -> Result<()> {
+        this.validate_credentials(&account).await?;
+
+        let mut task_pool = TaskPool::bounded(this.args.max_downloads)
+            .with_failure_policy(this.args.failure_policy());
+
+        let mut page = 1;
+        loop {
+            let (solutions, meta) = task_pool
+                .run_until(this.get_solutions_for_page(&account, page))
+                .await?;
+
+            let mut changed_solutions = Vec::new();
+            let mut found_unchanged = false;
+            for solution in solutions {
+                let solution_output_path = output_path
+                    .join(&solution.track.name)
+                    .join(&solution.exercise.name);
+                match this
+                    .solution_changed(&solution, &solution_output_path)
+                    .await
+                {
+                    true => changed_solutions.push(solution),
+                    false => found_unchanged = true,
+                }
+            }
 
-            if meta.current_page == meta.total_pages {
+            if !changed_solutions.is_empty() {
+                info!("Number of changed solutions in page {page}: {}", changed_solutions.len());
+                this.create_track_directories(&output_path, &changed_solutions)
+                    .await?;
+            }
+            for solution in changed_solutions {
+                let num_changed = num_changed.clone();
+                task_pool.spawn_with_subtasks(TaskPriority::High, |spawner| {
+                    let backup = Self::backup_solution(
+                        this.clone(),
+                        account.clone(),
+                        output_path.clone(),
+                        solution,
+                        true,
+                        spawner,
+                    );
+                    async move {
+                        backup.await?;
+                        num_changed.fetch_add(1, SeqCst);
+                        Ok(())
+                    }
+                });
+            }
+
+            if found_unchanged || meta.current_page >= meta.total_pages {
                 break;
             }
             page += 1;
         }
 
         task_pool
-            .join(|| "errors detected while backing up solutions")
+            .join(|| "errors detected while backing up changed solutions")
             .await
     }
 
-    #[instrument(level = "debug", skip_all, fields(%solution.track.name, %solution.exercise.name))]
+    /// Determines if the latest iteration of a solution differs from the one that was backed up,
+    /// using the solution's metadata. Solutions without metadata are considered changed.
+    async fn solution_changed(&self, solution: &Solution, solution_output_path: &Path) -> bool {
+        match read_solution_metadata(solution_output_path, self.cipher.as_ref()).await {
+            Ok(Some(metadata)) => {
+                metadata.num_iterations != solution.num_iterations
+                    || metadata.last_iterated_at != solution.last_iterated_at
+            },
+            Ok(None) => true,
+            Err(error) => {
+                debug!(
+                    "Failed to read metadata of solution to {}/{}: {error:#}",
+                    solution.track.name, solution.exercise.name
+                );
+                true
+            },
+        }
+    }
+
+    /// Spawns the backup of a solution in `task_pool` (see [`backup_solution`](Self::backup_solution)).
+    fn spawn_solution_backup(
+        this: &Arc<Self>,
+        task_pool: &mut TaskPool,
+        account: &Arc<Account>,
+        output_path: &Path,
+        solution: Solution,
+        update: bool,
+    ) {
+        task_pool.spawn_with_subtasks(TaskPriority::High, |spawner| {
+            Self::backup_solution(this.clone(), account.clone(), output_path.to_path_buf(), solution, update, spawner)
+        });
+    }
+
+    /// Backs up a solution, scoping errors to it for [reporting](ErrorReport).
+    ///
+    /// The solution's files are downloaded as subtasks using `spawner`: the solution itself should
+    /// be spawned with [`TaskPriority::High`], so that file lists are fetched before the files of
+    /// other solutions waiting in the pool.
+    ///
+    /// See [`create_solution_directory`](Self::create_solution_directory) for the meaning of `update`.
     async fn backup_solution(
         this: Arc<Self>,
+        account: Arc<Account>,
+        output_path: PathBuf,
+        solution: Solution,
+        update: bool,
+        mut spawner: TaskSpawner,
+    ) -> Result<()> {
+        let scope = ErrorScope::Solution {
+            track: solution.track.name.clone(),
+            exercise: solution.exercise.name.clone(),
+        };
+        Self::download_solution(this, account, output_path, solution, update, &mut spawner)
+            .await
+            .map_err(|error| scope.wrap(error))
+    }
+
+    #[instrument(level = "debug", skip_all, fields(%solution.track.name, %solution.exercise.name))]
+    async fn download_solution(
+        this: Arc<Self>,
+        account: Arc<Account>,
         mut output_path: PathBuf,
         solution: Solution,
+        update: bool,
+        spawner: &mut TaskSpawner,
     ) -> Result<()> {
         if !this.args.dry_run {
             debug!("Starting solution backup");
@@ -161,32 +541,39 @@ impl BackupCommand {
         output_path.push(&solution.exercise.name);
         trace!(output_path = %output_path.display());
 
-        match this.create_solution_directory(&solution, &output_path).await {
+        match this.create_solution_directory(&solution, &output_path, update).await {
+            Some(Ok(())) if this.args.verify_existing => {
+                return Self::verify_solution(this, account, solution, output_path, spawner).await;
+            },
             Some(Ok(())) => {
                 info!("Solution to {}/{} already exists; skipped.", solution.track.name, solution.exercise.name);
+                return Ok(());
             },
             Some(Err(error)) => return Err(error),
             None => (),
         }
 
         let files = {
-            let _permit = this.limiter.get_permit();
-            this.v1_client.get_solution(&solution.uuid).await?.solution.files
+            let _permit = this.limiter.get_permit().await;
+            this.with_read_timeout(account.v1_client.get_solution(&solution.uuid))
+                .await??
+                .solution
+                .files
         };
         if this.args.dry_run {
             debug!("Files to backup: {}", files.join(", "));
         }
 
         if !this.args.dry_run || enabled!(Level::TRACE) {
-            let mut task_pool = TaskPool::new();
-
-            for file in files {
-                task_pool.spawn(Self::backup_one_file(this.clone(), solution.clone(), file, output_path.clone()));
+            let checksums = Self::download_files(this.clone(), account, &solution, files, &output_path, spawner).await?;
+            if !this.args.dry_run {
+                this.write_checksums(&checksums, &output_path).await?;
             }
+        }
 
-            task_pool
-                .join(|| format!("errors detected while backing up solution for {}/{}", solution.track.name, solution.exercise.name))
-                .await?;
+        if !this.args.dry_run {
+            this.write_solution_metadata(&solution, &output_path).await?;
+            this.set_solution_times(&solution, &output_path).await?;
         }
 
         info!("Solution to {}/{} downloaded", solution.track.name, solution.exercise.name);
@@ -194,15 +581,113 @@ impl BackupCommand {
         Ok(())
     }
 
-    #[instrument(level = "trace", skip_all, fields(%solution.track.name, %solution.exercise.name, file))]
-    async fn backup_one_file(
+    /// Checks the files of an existing solution against its checksums, downloading corrupt or
+    /// missing files again. Solutions without checksums (backed up by an older version of `exsb`)
+    /// cannot be verified and are skipped.
+    #[instrument(level = "debug", skip_all, fields(%solution.track.name, %solution.exercise.name))]
+    async fn verify_solution(
         this: Arc<Self>,
+        account: Arc<Account>,
         solution: Solution,
+        output_path: PathBuf,
+        spawner: &mut TaskSpawner,
+    ) -> Result<()> {
+        let Some(mut checksums) = read_checksums(&output_path).await? else {
+            info!("Solution to {}/{} already exists but has no checksums; skipped.", solution.track.name, solution.exercise.name);
+            return Ok(());
+        };
+
+        let mut corrupt_files = Vec::new();
+        for (file, checksum) in &checksums {
+            let mut file_path = output_path.clone();
+            file_path.extend(file.split('/'));
+            match fs::read(&file_path).await {
+                Ok(data) if sha256_hex(&data) == *checksum => (),
+                _ => corrupt_files.push(file.clone()),
+            }
+        }
+        if corrupt_files.is_empty() {
+            info!("Solution to {}/{} already exists and is intact; skipped.", solution.track.name, solution.exercise.name);
+            return Ok(());
+        }
+
+        warn!(
+            "Corrupt or missing file(s) in solution to {}/{}: {}",
+            solution.track.name,
+            solution.exercise.name,
+            corrupt_files.join(", "),
+        );
+        if !this.args.dry_run {
+            checksums.extend(Self::download_files(this.clone(), account, &solution, corrupt_files, &output_path, spawner).await?);
+            this.write_checksums(&checksums, &output_path).await?;
+            this.set_solution_times(&solution, &output_path).await?;
+            info!("Corrupt file(s) in solution to {}/{} downloaded again", solution.track.name, solution.exercise.name);
+        }
+
+        Ok(())
+    }
+
+    /// Downloads the given files of a solution, returning their checksums.
+    ///
+    /// Files are downloaded as subtasks of the solution, so they share the account's pool (and its
+    /// bound on concurrent downloads) with other solutions. The API doesn't return file sizes, so
+    /// files are downloaded in the order they are listed.
+    async fn download_files(
+        this: Arc<Self>,
+        account: Arc<Account>,
+        solution: &Solution,
+        files: Vec<String>,
+        output_path: &Path,
+        spawner: &mut TaskSpawner,
+    ) -> Result<Checksums> {
+        let checksums = Arc::new(Mutex::new(Checksums::new()));
+        let solution = Arc::new(solution.clone());
+
+        for file in files {
+            spawner.spawn_with_priority(TaskPriority::Normal, Self::backup_one_file(this.clone(), account.clone(), solution.clone(), file, output_path.to_path_buf(), checksums.clone()));
+        }
+
+        spawner
+            .join(|| format!("errors detected while backing up solution for {}/{}", solution.track.name, solution.exercise.name))
+            .await?;
+
+        let checksums = checksums.lock().expect("checksums lock should not be poisoned");
+        Ok(checksums.clone())
+    }
+
+    /// Backs up a file of a solution, scoping errors to it for [reporting](ErrorReport).
+    async fn backup_one_file(
+        this: Arc<Self>,
+        account: Arc<Account>,
+        solution: Arc<Solution>,
         file: String,
-        mut destination_path: PathBuf,
+        destination_path: PathBuf,
+        checksums: Arc<Mutex<Checksums>>,
     ) -> Result<()> {
-        let _permit = this.limiter.get_permit();
-        let mut file_stream = this.v1_client.get_file(&solution.uuid, &file).await;
+        let scope = ErrorScope::File(file.clone());
+        let checksum = Self::download_file(this, account, solution, file.clone(), destination_path)
+            .await
+            .map_err(|error| scope.wrap(error))?;
+
+        checksums
+            .lock()
+            .expect("checksums lock should not be poisoned")
+            .insert(file, checksum);
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip_all, fields(%solution.track.name, %solution.exercise.name, file))]
+    async fn download_file(
+        this: Arc<Self>,
+        account: Arc<Account>,
+        solution: Arc<Solution>,
+        file: String,
+        mut destination_path: PathBuf,
+    ) -> Result<String> {
+        let _permit = this.limiter.get_permit().await;
+        let mut file_stream = this
+            .with_read_timeout(account.v1_client.get_file(&solution.uuid, &file))
+            .await?;
 
         destination_path.extend(file.split('/'));
         trace!(destination_path = %destination_path.display());
@@ -217,14 +702,39 @@ impl BackupCommand {
                 })?;
             let mut destination_file = BufWriter::new(destination_file);
 
-            while let Some(bytes) = file_stream.next().await {
+            // Encrypted files are authenticated as a whole, so we need to accumulate their content.
+            let mut plaintext = this.cipher.as_ref().map(|_| Vec::new());
+
+            // Checksums are those of the data written to disk, so that `sha256sum -c` can check them.
+            let mut hasher = Sha256::new();
+
+            while let Some(bytes) = this.with_read_timeout(file_stream.next()).await? {
                 let bytes = bytes.with_context(|| {
                     format!(
                         "failed to download file {} in solution to exercise {}/{}",
                         file, solution.track.name, solution.exercise.name,
                     )
                 })?;
-                destination_file.write_all(&bytes).await.with_context(|| {
+                if let Some(rate_limiter) = &this.rate_limiter {
+                    rate_limiter.consume(bytes.len()).await;
+                }
+                match plaintext.as_mut() {
+                    Some(plaintext) => plaintext.extend_from_slice(&bytes),
+                    None => {
+                        hasher.update(&bytes);
+                        destination_file.write_all(&bytes).await.with_context(|| {
+                            format!("failed to write data to file {}", destination_path.display())
+                        })?
+                    },
+                }
+            }
+
+            if let (Some(cipher), Some(plaintext)) = (&this.cipher, plaintext) {
+                let encrypted = cipher.encrypt(&plaintext).with_context(|| {
+                    format!("failed to encrypt file {}", destination_path.display())
+                })?;
+                hasher.update(&encrypted);
+                destination_file.write_all(&encrypted).await.with_context(|| {
                     format!("failed to write data to file {}", destination_path.display())
                 })?;
             }
@@ -232,11 +742,99 @@ impl BackupCommand {
             destination_file.flush().await.with_context(|| {
                 format!("failed to flush data to file {}", destination_path.display())
             })?;
+
+            return Ok(format!("{:x}", hasher.finalize()));
+        }
+
+        Ok(String::new())
+    }
+
+    /// Stores checksums of the solution's files, in a format that can be checked with `sha256sum -c`.
+    #[instrument(level = "trace", skip_all)]
+    async fn write_checksums(&self, checksums: &Checksums, solution_output_path: &Path) -> Result<()> {
+        let checksums_path = checksums_path(solution_output_path);
+        trace!(checksums_path = %checksums_path.display());
+
+        self.create_file_parent_directory(&checksums_path).await?;
+        fs::write(&checksums_path, format_checksums(checksums))
+            .await
+            .with_context(|| format!("failed to write file {}", checksums_path.display()))
+    }
+
+    /// Stores the solution's metadata alongside its files, to be used by other commands (like `stats`).
+    #[instrument(level = "trace", skip_all)]
+    async fn write_solution_metadata(&self, solution: &Solution, solution_output_path: &Path) -> Result<()> {
+        let metadata_path = solution_output_path
+            .join(METADATA_DIR)
+            .join(SOLUTION_METADATA_FILE);
+        trace!(metadata_path = %metadata_path.display());
+
+        let metadata = serde_json::to_vec_pretty(solution).with_context(|| {
+            format!(
+                "failed to serialize metadata of solution to {}/{}",
+                solution.track.name, solution.exercise.name
+            )
+        })?;
+        let metadata = match &self.cipher {
+            Some(cipher) => cipher.encrypt(&metadata).with_context(|| {
+                format!("failed to encrypt file {}", metadata_path.display())
+            })?,
+            None => metadata,
+        };
+
+        self.create_file_parent_directory(&metadata_path).await?;
+        fs::write(&metadata_path, metadata)
+            .await
+            .with_context(|| format!("failed to write file {}", metadata_path.display()))
+    }
+
+    /// Sets the modification time of the solution's files and directories to its submission time,
+    /// so that backing up the same solution again results in an identical tree (except for the
+    /// content of encrypted files, since each encryption uses a random salt and nonce).
+    #[instrument(level = "trace", skip_all)]
+    async fn set_solution_times(&self, solution: &Solution, solution_output_path: &Path) -> Result<()> {
+        match submission_time(solution) {
+            Some(time) => set_modification_times(solution_output_path, time).await,
+            None => {
+                trace!(%solution.updated_at, "Invalid submission time; modification times left as is");
+                Ok(())
+            },
+        }
+    }
+
+    /// Sets the modification time of the track directories of an account to the latest one of
+    /// their solutions (see [`set_solution_times`](Self::set_solution_times)), then that of the
+    /// account's own directory if `account_directory` is `true`.
+    ///
+    /// This is done once all solutions of the account have been backed up, even if some failed.
+    #[instrument(level = "trace", skip(self))]
+    async fn set_directory_times(&self, account_output_path: &Path, account_directory: bool) -> Result<()> {
+        if self.args.dry_run || !account_output_path.is_dir() {
+            return Ok(());
+        }
+
+        for track in list_subdirectories(account_output_path).await? {
+            set_modification_time_from_subdirectories(&account_output_path.join(track)).await?;
+        }
+        if account_directory {
+            set_modification_time_from_subdirectories(account_output_path).await?;
         }
 
         Ok(())
     }
 
+    async fn with_read_timeout<F>(&self, future: F) -> Result<F::Output>
+    where
+        F: Future,
+    {
+        match self.read_timeout {
+            Some(read_timeout) => timeout(read_timeout, future).await.map_err(|_| {
+                anyhow!("timed out after {} second(s) waiting for data", read_timeout.as_secs())
+            }),
+            None => Ok(future.await),
+        }
+    }
+
     #[instrument(skip(self))]
     async fn create_output_directory(&self, output_path: &Path) -> Result<()> {
         match self.args.dry_run {
@@ -249,22 +847,89 @@ impl BackupCommand {
         }
     }
 
-    #[instrument(skip(self), ret(level = "trace"))]
-    async fn get_solutions_for_page(&self, page: i64) -> Result<(Vec<Solution>, solutions::ResponseMeta)> {
+    #[instrument(skip(self, account), ret(level = "trace"))]
+    async fn get_solutions_for_page(&self, account: &Account, page: i64) -> Result<(Vec<Solution>, solutions::ResponseMeta)> {
         let paging = solutions::Paging::for_page(page);
 
-        let _permit = self.limiter.get_permit();
-        let response = self.v2_client
-            .get_solutions(None, Some(paging), Some(solutions::SortOrder::NewestFirst))
-            .await
-            .with_context(|| format!("failed to fetch solutions for page {page}"))?;
+        let response = {
+            let _permit = self.limiter.get_permit().await;
+            self.with_read_timeout(account.v2_client.get_solutions(
+                None,
+                Some(paging),
+                Some(solutions::SortOrder::NewestFirst),
+            ))
+            .await?
+            .with_context(|| format!("failed to fetch solutions for page {page}"))?
+        };
+        self.known_solutions
+            .lock()
+            .expect("known solutions lock should not be poisoned")
+            .extend(response.results.iter().map(KnownSolution::from));
         let solutions = response.results
             .into_iter()
             .filter(|solution| self.args.solution_matches(solution))
             .collect();
+        let solutions = self.filter_exercise_types(account, solutions).await?;
         Ok((solutions, response.meta))
     }
 
+    /// Adds solutions seen while fetching pages to the list of [known solutions](known_solutions).
+    ///
+    /// The list is only saved once, since pages are fetched concurrently.
+    async fn save_known_solutions(&self) {
+        let known_solutions = std::mem::take(
+            &mut *self
+                .known_solutions
+                .lock()
+                .expect("known solutions lock should not be poisoned"),
+        );
+        known_solutions::add(known_solutions).await;
+    }
+
+    /// Keeps the solutions to exercises of the types selected with `--exercise-type`.
+    ///
+    /// Exercise types are fetched once per track and shared by all accounts.
+    async fn filter_exercise_types(&self, account: &Account, solutions: Vec<Solution>) -> Result<Vec<Solution>> {
+        if self.args.properties.exercise_type.is_empty() {
+            return Ok(solutions);
+        }
+
+        // The lock is held while fetching, so that each track's exercises are only fetched once.
+        let mut exercise_types = self.exercise_types.lock().await;
+        let mut matching_solutions = Vec::new();
+        for solution in solutions {
+            let track_name = &solution.track.name;
+            if !exercise_types.contains_key(track_name) {
+                let track_exercise_types = self.get_exercise_types(account, track_name).await?;
+                exercise_types.insert(track_name.clone(), track_exercise_types);
+            }
+
+            let exercise_type = exercise_types[track_name]
+                .get(&solution.exercise.name)
+                .copied();
+            if self.args.properties.exercise_type_matches(exercise_type) {
+                matching_solutions.push(solution);
+            }
+        }
+
+        Ok(matching_solutions)
+    }
+
+    #[instrument(skip(self, account), ret(level = "trace"))]
+    async fn get_exercise_types(&self, account: &Account, track_name: &str) -> Result<HashMap<String, ExerciseType>> {
+        let _permit = self.limiter.get_permit().await;
+        let response = self
+            .with_read_timeout(account.v2_client.get_exercises(track_name, None))
+            .await?
+            .with_context(|| format!("failed to fetch exercises of track {track_name}"))?;
+
+        Ok(response
+            .exercises
+            .into_iter()
+            .filter_map(|exercise| Some((exercise.name, exercise.exercise_type.try_into().ok()?)))
+            .collect())
+    }
+
     #[instrument(skip_all)]
     async fn create_track_directories(
         &self,
@@ -292,22 +957,40 @@ impl BackupCommand {
         Ok(())
     }
 
+    /// Creates the directory of a solution, returning [`Some`] if the solution should not be
+    /// downloaded (because it already exists, or because of an error).
+    ///
+    /// An existing solution is downloaded again if [`force`](BackupArgs::force) is set (after
+    /// removing its directory) or if `update` is set (overwriting its files, but keeping other files
+    /// in its directory). In both cases, its files are archived first if
+    /// [`keep_iterations`](BackupArgs::keep_iterations) is set.
     #[instrument(skip_all)]
-    async fn create_solution_directory(&self, solution: &Solution, solution_output_path: &Path) -> Option<Result<()>> {
+    async fn create_solution_directory(
+        &self,
+        solution: &Solution,
+        solution_output_path: &Path,
+        update: bool,
+    ) -> Option<Result<()>> {
         if fs::metadata(solution_output_path)
             .await
             .map(|meta| meta.is_dir())
             .unwrap_or(false)
         {
-            if self.args.force {
+            if self.args.force || update {
                 trace!("Solution already exists on disk; cleaning up...");
                 if !self.args.dry_run {
-                    fs::remove_dir_all(solution_output_path)
-                        .await
-                        .with_context(|| {
-                            format!("failed to clean up existing directory {}", solution_output_path.display())
-                        })
-                        .err()?;
+                    let cleanup = if self.args.keep_iterations {
+                        self.archive_solution_iteration(solution_output_path).await
+                    } else if update {
+                        Ok(())
+                    } else {
+                        fs::remove_dir_all(solution_output_path)
+                            .await
+                            .with_context(|| {
+                                format!("failed to clean up existing directory {}", solution_output_path.display())
+                            })
+                    };
+                    cleanup.err()?;
                 }
             } else {
                 trace!("Solution already exists on disk; skipping");
@@ -332,6 +1015,52 @@ impl BackupCommand {
         None
     }
 
+    /// Moves the files of an existing solution to its iterations directory, so that they are
+    /// kept when the solution is downloaded again.
+    #[instrument(skip_all)]
+    async fn archive_solution_iteration(&self, solution_output_path: &Path) -> Result<()> {
+        let existing_numbers = iteration_numbers(solution_output_path).await?;
+        let number = match read_solution_metadata(solution_output_path, self.cipher.as_ref()).await {
+            Ok(Some(metadata)) => u32::try_from(metadata.num_iterations).ok(),
+            _ => None,
+        }
+        .unwrap_or_else(|| existing_numbers.last().map_or(1, |last| last + 1));
+
+        let iteration_path = iterations_path(solution_output_path).join(number.to_string());
+        let archive = !existing_numbers.contains(&number);
+        if archive {
+            trace!(iteration_path = %iteration_path.display(), "Archiving previous iteration");
+            fs::create_dir_all(&iteration_path)
+                .await
+                .with_context(|| format!("failed to create directory {}", iteration_path.display()))?;
+        } else {
+            trace!("Iteration {number} is already archived; cleaning up...");
+        }
+
+        let mut entries = fs::read_dir(solution_output_path)
+            .await
+            .with_context(|| format!("failed to list directory {}", solution_output_path.display()))?;
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .with_context(|| format!("failed to list directory {}", solution_output_path.display()))?
+        {
+            if entry.file_name() == METADATA_DIR {
+                continue;
+            }
+
+            let entry_path = entry.path();
+            let result = match (archive, entry_path.is_dir()) {
+                (true, _) => fs::rename(&entry_path, iteration_path.join(entry.file_name())).await,
+                (false, true) => fs::remove_dir_all(&entry_path).await,
+                (false, false) => fs::remove_file(&entry_path).await,
+            };
+            result.with_context(|| format!("failed to clean up {}", entry_path.display()))?;
+        }
+
+        Ok(())
+    }
+
     #[instrument(skip_all)]
     async fn create_file_parent_directory(&self, destination_path: &Path) -> Result<()> {
         match destination_path.parent() {
@@ -347,3 +1076,11 @@ impl BackupCommand {
         }
     }
 }
+
+/// Writes an error report as JSON. An empty report is written if the backup succeeded.
+async fn write_error_report(path: &Path, report: &ErrorReport) -> Result<()> {
+    let json = serde_json::to_vec_pretty(report).with_context(|| "failed to serialize error report")?;
+    fs::write(path, json)
+        .await
+        .with_context(|| format!("failed to write error report to {}", path.display()))
+}
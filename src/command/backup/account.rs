@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+
+use anyhow::{anyhow, Context};
+use mini_exercism::api;
+use mini_exercism::core::Credentials;
+
+use crate::credentials::CredentialSource;
+use crate::Result;
+
+/// Exercism account to back up, along with the API clients used to access it.
+///
+/// When multiple accounts are backed up in a single run, each one has a `name` and is stored in
+/// its own subdirectory of the output path.
+#[derive(Debug)]
+pub(crate) struct Account {
+    pub name: Option<String>,
+    pub credential_source: CredentialSource,
+    pub v1_client: api::v1::Client,
+    pub v2_client: api::v2::Client,
+
+    /// Whether the account's token has been validated; commands backing up repeatedly (like
+    /// `watch`) only validate it once.
+    pub credentials_validated: AtomicBool,
+}
+
+impl Account {
+    pub fn new(
+        name: Option<String>,
+        http_client: &reqwest::Client,
+        credentials: &Credentials,
+        credential_source: CredentialSource,
+        api_base_url: Option<&str>,
+    ) -> Self {
+        let v1_client = build_client!(api::v1::Client, http_client, credentials, api_base_url);
+        let v2_client = build_client!(api::v2::Client, http_client, credentials, api_base_url);
+
+        Self {
+            name,
+            credential_source,
+            v1_client,
+            v2_client,
+            credentials_validated: AtomicBool::new(false),
+        }
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or("default")
+    }
+}
+
+/// Reads named account tokens from the given file.
+///
+/// See [`parse_accounts`] for the expected file format.
+pub(crate) fn read_accounts_file(path: &Path) -> Result<Vec<(String, Credentials)>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read accounts file {}", path.display()))?;
+
+    parse_accounts(&content)
+        .with_context(|| format!("invalid accounts file {}", path.display()))
+        .map(|accounts| {
+            accounts
+                .into_iter()
+                .map(|(name, token)| (name, Credentials::from_api_token(token)))
+                .collect()
+        })
+}
+
+/// Parses named account tokens.
+///
+/// Each non-empty line must be in the form `name = token`. Lines starting with `#` are ignored.
+/// Account names are used as directory names, so they must be unique and cannot contain
+/// path separators.
+fn parse_accounts(content: &str) -> Result<Vec<(String, String)>> {
+    let mut names = HashSet::new();
+
+    let accounts = content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_number, line)| {
+            let (name, token) = line
+                .split_once('=')
+                .map(|(name, token)| (name.trim(), token.trim()))
+                .filter(|(name, token)| !name.is_empty() && !token.is_empty())
+                .ok_or_else(|| anyhow!("line {line_number}: expected `name = token`"))?;
+
+            if name == "." || name == ".." || name.contains(['/', '\\']) {
+                return Err(anyhow!("line {line_number}: invalid account name '{name}'"));
+            }
+            if !names.insert(name.to_string()) {
+                return Err(anyhow!("line {line_number}: duplicate account name '{name}'"));
+            }
+
+            Ok((name.to_string(), token.to_string()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    match accounts.is_empty() {
+        true => Err(anyhow!("no accounts found")),
+        false => Ok(accounts),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_accounts() {
+        let content = "# Our accounts\npersonal = abc123\n\n  team-bot=def456  \n";
+
+        let accounts = parse_accounts(content).unwrap();
+        assert_eq!(
+            vec![
+                ("personal".to_string(), "abc123".to_string()),
+                ("team-bot".into(), "def456".into())
+            ],
+            accounts
+        );
+    }
+
+    #[test]
+    fn test_parse_accounts_errors() {
+        assert!(parse_accounts("").is_err());
+        assert!(parse_accounts("# only comments").is_err());
+        assert!(parse_accounts("personal").is_err());
+        assert!(parse_accounts("personal =").is_err());
+        assert!(parse_accounts("../evil = abc123").is_err());
+        assert!(parse_accounts("personal = abc\npersonal = def").is_err());
+    }
+}
@@ -1,10 +1,17 @@
 //! Arguments that can be passed to the [`Backup`](crate::command::Command::Backup) command.
 
 use std::path::PathBuf;
+use std::str::FromStr;
 
 use clap::{Args, ValueEnum};
-use mini_exercism::api::v2::solution;
+use mini_exercism::api::v2::{exercise, solution};
 use mini_exercism::api::v2::solution::Solution;
+use serde::Serialize;
+
+use crate::command::args::{
+    CacheArgs, CredentialsArgs, EncryptionArgs, HttpClientArgs, SolutionFilterArgs,
+};
+use crate::task_pool::FailurePolicy;
 
 /// Command-line arguments accepted by the [`Backup`](crate::command::Command::Backup) command.
 #[derive(Debug, Clone, Args)]
@@ -12,26 +19,43 @@ pub struct BackupArgs {
     /// Path where to store the downloaded solutions
     pub path: PathBuf,
 
-    /// Exercism.org API token; if unspecified, CLI token will be used instead
-    #[arg(long)]
-    pub token: Option<String>,
+    /// Exercism.org API token to use
+    #[command(flatten)]
+    pub credentials: CredentialsArgs,
 
-    /// Only download solutions in the given track(s) (can be used multiple times)
-    #[arg(short, long)]
-    pub track: Vec<String>,
+    /// File containing named tokens of multiple accounts to backup (one `name = token` per line)
+    ///
+    /// Each account is backed up in its own subdirectory of the output path.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["token", "token_file"])]
+    pub accounts_file: Option<PathBuf>,
 
-    /// Only download solutions for the given exercise(s) (can be used multiple times)
-    #[arg(short, long)]
-    pub exercise: Vec<String>,
+    /// Filters used to select solutions to download
+    #[command(flatten)]
+    pub filter: SolutionFilterArgs,
 
-    /// Only download solutions with the given status (or greater)
-    #[arg(short, long, value_enum, default_value_t = SolutionStatus::Submitted)]
-    pub status: SolutionStatus,
+    /// Filters used to select solutions to download using their other properties
+    #[command(flatten)]
+    pub properties: SolutionPropertyFilterArgs,
 
     /// Overwrite exercises that have already been downloaded
     #[arg(short, long, default_value_t = false)]
     pub force: bool,
 
+    /// When overwriting a solution with --force, keep the previous copy as an older iteration
+    ///
+    /// Previous copies are stored in the solution's .exsb/iterations directory. They can be
+    /// browsed and compared with the latest iteration using other commands (like export-html).
+    #[arg(long, default_value_t = false, requires = "force")]
+    pub keep_iterations: bool,
+
+    /// Check files of existing solutions against their checksums, downloading corrupt or
+    /// missing files again
+    ///
+    /// Checksums are stored in each solution's .exsb/SHA256SUMS file. Solutions backed up by a
+    /// version of exsb that did not store checksums cannot be verified.
+    #[arg(long, default_value_t = false, conflicts_with = "force")]
+    pub verify_existing: bool,
+
     /// Determine what solutions to backup without downloading them
     #[arg(long, default_value_t = false)]
     pub dry_run: bool,
@@ -39,10 +63,135 @@ pub struct BackupArgs {
     /// Maximum number of concurrent downloads
     #[arg(short, long, default_value_t = 4)]
     pub max_downloads: usize,
+
+    /// Maximum download rate, in bytes per second, across all downloads (e.g. `500K` or `2M`)
+    ///
+    /// Suffixes `K`, `M` and `G` are multiples of 1024.
+    #[arg(long, value_name = "RATE")]
+    pub limit_rate: Option<ByteRate>,
+
+    /// Stop backing up an account as soon as one of its solutions fails to be backed up
+    #[arg(long, default_value_t = false, conflicts_with = "max_failures")]
+    pub fail_fast: bool,
+
+    /// Stop backing up an account once more than this number (e.g. `10`) or percentage (e.g. `5%`)
+    /// of its solutions failed to be backed up
+    ///
+    /// Percentages are relative to the number of solutions backed up so far, and are only checked
+    /// once at least 10 solutions have been backed up. This avoids hammering the Exercism API
+    /// during an outage. By default, all solutions are attempted.
+    #[arg(long, value_name = "N[%]")]
+    pub max_failures: Option<MaxFailures>,
+
+    /// Write a JSON report of the errors that occurred, grouped by account, track, exercise and file
+    ///
+    /// The report is written even if the backup succeeds (in which case it is empty), so that a
+    /// stale report is never left behind.
+    #[arg(long, value_name = "FILE")]
+    pub error_report: Option<PathBuf>,
+
+    /// Configuration of the HTTP client used to access the Exercism API
+    #[command(flatten)]
+    pub http: HttpClientArgs,
+
+    /// Configuration of the cache of Exercism API responses
+    #[command(flatten)]
+    pub cache: CacheArgs,
+
+    /// Base URL of the Exercism API (only used for testing, see `exsb dev fake-server`)
+    #[arg(long, hide = true)]
+    pub api_base_url: Option<String>,
+
+    /// Key used to encrypt downloaded files (if unspecified, files are stored in plaintext)
+    #[command(flatten)]
+    pub encryption: EncryptionArgs,
+
+    /// Record all exchanges with the Exercism API to the given directory
+    ///
+    /// The API token is never recorded, so the session can be attached to bug reports. It can
+    /// then be replayed with --replay.
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["replay", "accounts_file"])]
+    pub record: Option<PathBuf>,
+
+    /// Replay a session recorded with --record instead of accessing the Exercism API
+    ///
+    /// No API token is needed to replay a session.
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["accounts_file", "api_base_url"])]
+    pub replay: Option<PathBuf>,
 }
 
-/// Possible solution status to filter for (see [`BackupArgs::status`]).
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+/// Command-line arguments used to select solutions by properties returned by the Exercism API
+/// (in addition to those of [`SolutionFilterArgs`]).
+#[derive(Debug, Clone, Default, Args)]
+pub struct SolutionPropertyFilterArgs {
+    /// Only include solutions to exercises that have been updated since they were solved
+    #[arg(long, default_value_t = false)]
+    pub out_of_date: bool,
+
+    /// Only include solutions with the given mentoring status(es)
+    ///
+    /// For example, use `--mentoring-status in-progress,finished` to include all mentored solutions.
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub mentoring_status: Vec<MentoringStatus>,
+
+    /// Only include published solutions
+    #[arg(long, default_value_t = false, conflicts_with = "unpublished")]
+    pub published: bool,
+
+    /// Only include solutions that have not been published
+    #[arg(long, default_value_t = false)]
+    pub unpublished: bool,
+
+    /// Only include solutions starred at least this number of times
+    #[arg(long, value_name = "N")]
+    pub min_stars: Option<u32>,
+
+    /// Only include solutions with at least this number of iterations
+    #[arg(long, value_name = "N")]
+    pub min_iterations: Option<u32>,
+
+    /// Only include solutions to exercises of the given type(s)
+    ///
+    /// Exercise types are not part of solutions, so they are fetched from the Exercism API, once
+    /// per track.
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub exercise_type: Vec<ExerciseType>,
+}
+
+impl SolutionPropertyFilterArgs {
+    /// Determines if a solution matches these filters, except for the
+    /// [exercise type](Self::exercise_type_matches).
+    pub fn matches(&self, solution: &Solution) -> bool {
+        (!self.out_of_date || solution.is_out_of_date)
+            && self.mentoring_status_matches(solution.mentoring_status.try_into().ok())
+            && (!self.published || solution.published_at.is_some())
+            && (!self.unpublished || solution.published_at.is_none())
+            && self
+                .min_stars
+                .map_or(true, |min_stars| i64::from(solution.num_stars) >= i64::from(min_stars))
+            && self.min_iterations.map_or(true, |min_iterations| {
+                i64::from(solution.num_iterations) >= i64::from(min_iterations)
+            })
+    }
+
+    /// Determines if an exercise type matches these filters.
+    ///
+    /// An `exercise_type` of [`None`] (e.g. for exercises whose type is unknown) only matches
+    /// if all types are accepted.
+    pub fn exercise_type_matches(&self, exercise_type: Option<ExerciseType>) -> bool {
+        self.exercise_type.is_empty()
+            || exercise_type.map_or(false, |exercise_type| self.exercise_type.contains(&exercise_type))
+    }
+
+    fn mentoring_status_matches(&self, mentoring_status: Option<MentoringStatus>) -> bool {
+        self.mentoring_status.is_empty()
+            || mentoring_status.map_or(false, |status| self.mentoring_status.contains(&status))
+    }
+}
+
+/// Possible solution status to filter for (see [`SolutionFilterArgs::status`]).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SolutionStatus {
     /// At least one iteration has been submitted, but exercise has not been marked as complete
     Submitted,
@@ -67,23 +216,157 @@ impl TryFrom<solution::Status> for SolutionStatus {
     }
 }
 
+/// Possible mentoring status to filter for (see [`SolutionPropertyFilterArgs::mentoring_status`]).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum MentoringStatus {
+    /// Mentoring has been requested, but no mentor has started a discussion yet
+    Requested,
+
+    /// A mentoring discussion is in progress
+    InProgress,
+
+    /// A mentoring discussion has been completed
+    Finished,
+}
+
+impl TryFrom<solution::MentoringStatus> for MentoringStatus {
+    type Error = ();
+
+    fn try_from(value: solution::MentoringStatus) -> Result<Self, Self::Error> {
+        match value {
+            solution::MentoringStatus::Requested => Ok(MentoringStatus::Requested),
+            solution::MentoringStatus::InProgress => Ok(MentoringStatus::InProgress),
+            solution::MentoringStatus::Finished => Ok(MentoringStatus::Finished),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Possible exercise type to filter for (see [`SolutionPropertyFilterArgs::exercise_type`]).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum ExerciseType {
+    /// Tutorial exercise, like `hello-world`
+    Tutorial,
+
+    /// Learning exercise, teaching a concept of the track's syllabus
+    Concept,
+
+    /// Practice exercise
+    Practice,
+}
+
+impl TryFrom<exercise::Type> for ExerciseType {
+    type Error = ();
+
+    fn try_from(value: exercise::Type) -> Result<Self, Self::Error> {
+        match value {
+            exercise::Type::Tutorial => Ok(ExerciseType::Tutorial),
+            exercise::Type::Concept => Ok(ExerciseType::Concept),
+            exercise::Type::Practice => Ok(ExerciseType::Practice),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Limit on the number of failed solutions (see [`BackupArgs::max_failures`]).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MaxFailures {
+    /// Maximum number of failed solutions
+    Count(usize),
+
+    /// Maximum percentage of failed solutions
+    Percent(u32),
+}
+
+impl FromStr for MaxFailures {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_suffix('%') {
+            Some(percent) => percent
+                .trim()
+                .parse()
+                .ok()
+                .filter(|percent| *percent <= 100)
+                .map(Self::Percent)
+                .ok_or_else(|| format!("invalid percentage {s}: expected a number from 0 to 100")),
+            None => s
+                .parse()
+                .map(Self::Count)
+                .map_err(|_| format!("invalid number of failures {s}: expected a number or a percentage")),
+        }
+    }
+}
+
+/// Download rate in bytes per second (see [`BackupArgs::limit_rate`]).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ByteRate(pub u64);
+
+impl FromStr for ByteRate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (number, multiplier) = match s.char_indices().last() {
+            Some((i, 'k' | 'K')) => (&s[..i], 1 << 10),
+            Some((i, 'm' | 'M')) => (&s[..i], 1 << 20),
+            Some((i, 'g' | 'G')) => (&s[..i], 1 << 30),
+            _ => (s, 1),
+        };
+
+        number
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .and_then(|number| number.checked_mul(multiplier))
+            .filter(|rate| *rate > 0)
+            .map(Self)
+            .ok_or_else(|| format!("invalid rate {s}: expected a number of bytes, optionally followed by K, M or G"))
+    }
+}
+
 impl BackupArgs {
+    /// Returns the policy used to determine when to stop backing up an account after failures.
+    pub(crate) fn failure_policy(&self) -> FailurePolicy {
+        match (self.fail_fast, self.max_failures) {
+            (true, _) => FailurePolicy::FailFast,
+            (false, Some(MaxFailures::Count(count))) => FailurePolicy::MaxFailures(count),
+            (false, Some(MaxFailures::Percent(percent))) => FailurePolicy::MaxFailureRate(percent),
+            (false, None) => FailurePolicy::Continue,
+        }
+    }
+
     /// Determines if the given [`Solution`] should be backed up.
+    ///
+    /// The [exercise type](SolutionPropertyFilterArgs::exercise_type) is not part of solutions,
+    /// so it must be checked separately.
     pub fn solution_matches(&self, solution: &Solution) -> bool {
-        self.track_matches(&solution.track.name) && self.exercise_matches(&solution.exercise.name) &&
-            self.solution_status_matches(solution.status.try_into().ok())
+        self.filter.matches(
+            &solution.track.name,
+            &solution.exercise.name,
+            solution.status.try_into().ok(),
+        ) && self.properties.matches(solution)
     }
+}
 
-    fn track_matches(&self, track_name: &str) -> bool {
-        self.track.is_empty() || self.track.iter().any(|t| t == track_name)
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    fn exercise_matches(&self, exercise_name: &str) -> bool {
-        self.exercise.is_empty() || self.exercise.iter().any(|e| e == exercise_name)
+    #[test]
+    fn test_max_failures_from_str() {
+        assert_eq!(Ok(MaxFailures::Count(10)), "10".parse());
+        assert_eq!(Ok(MaxFailures::Percent(5)), "5%".parse());
+        assert!("150%".parse::<MaxFailures>().is_err());
+        assert!("many".parse::<MaxFailures>().is_err());
     }
 
-    fn solution_status_matches(&self, solution_status: Option<SolutionStatus>) -> bool {
-        self.status == SolutionStatus::Submitted
-            || solution_status.map_or(false, |st| st >= self.status)
+    #[test]
+    fn test_byte_rate_from_str() {
+        assert_eq!(Ok(ByteRate(1000)), "1000".parse());
+        assert_eq!(Ok(ByteRate(500 * 1024)), "500K".parse());
+        assert_eq!(Ok(ByteRate(2 * 1024 * 1024)), "2m".parse());
+        assert!("0".parse::<ByteRate>().is_err());
+        assert!("fast".parse::<ByteRate>().is_err());
+        assert!("K".parse::<ByteRate>().is_err());
     }
 }
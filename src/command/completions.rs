@@ -0,0 +1,144 @@
+//! Definition of the [`Completions`](crate::command::Command::Completions) command.
+
+pub mod args;
+
+use std::collections::BTreeSet;
+use std::io::Write;
+
+use anyhow::Context;
+use clap::CommandFactory;
+use clap_complete::{generate, Shell};
+use tracing::{instrument, trace};
+
+use crate::command::completions::args::{CompletionValues, CompletionsArgs};
+use crate::known_solutions;
+use crate::{Cli, Result};
+
+/// Completion of `--track` and `--exercise` values for bash, added to the generated script.
+const BASH_DYNAMIC_COMPLETIONS: &str = r#"
+_exsb_with_values() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+    case "${prev}" in
+        --track|-t)
+            COMPREPLY=($(compgen -W "$(exsb completions --values track 2>/dev/null)" -- "${cur}"))
+            return 0
+            ;;
+        --exercise|-e)
+            COMPREPLY=($(compgen -W "$(exsb completions --values exercise 2>/dev/null)" -- "${cur}"))
+            return 0
+            ;;
+    esac
+    _exsb "$@"
+}
+
+complete -F _exsb_with_values -o bashdefault -o default exsb
+"#;
+
+/// Completion of `--track` and `--exercise` values for fish, added to the generated script.
+const FISH_DYNAMIC_COMPLETIONS: &str = r#"
+complete -c exsb -s t -l track -x -a "(exsb completions --values track 2>/dev/null)"
+complete -c exsb -s e -l exercise -x -a "(exsb completions --values exercise 2>/dev/null)"
+"#;
+
+/// Completion function for `--track` and `--exercise` values for zsh, added to the generated script.
+const ZSH_DYNAMIC_COMPLETIONS: &str = r#"
+(( $+functions[_exsb_known_values] )) ||
+_exsb_known_values() {
+    local -a values
+    values=(${(f)"$(exsb completions --values $1 2>/dev/null)"})
+    compadd -a values
+}
+"#;
+
+/// Command wrapper used for the [`Completions`](crate::command::Command::Completions) command.
+#[derive(Debug)]
+pub struct CompletionsCommand {
+    args: CompletionsArgs,
+}
+
+impl CompletionsCommand {
+    /// Creates a new [`CompletionsCommand`] using the provided [`args`](CompletionsArgs).
+    pub fn new(args: CompletionsArgs) -> Self {
+        Self { args }
+    }
+
+    /// Print the completion script (or the requested completion values) to standard output.
+    #[instrument(skip_all)]
+    pub async fn execute(&self) -> Result<()> {
+        trace!(?self.args);
+
+        let output = match (self.args.shell, self.args.values) {
+            (_, Some(values)) => completion_values(values),
+            (Some(shell), None) => completion_script(shell)?,
+            (None, None) => unreachable!("clap should require a shell or values"),
+        };
+
+        std::io::stdout()
+            .write_all(output.as_bytes())
+            .with_context(|| "failed to write to standard output")
+    }
+}
+
+fn completion_script(shell: Shell) -> Result<String> {
+    let mut command = Cli::command();
+    let bin_name = command.get_name().to_string();
+
+    let mut script = Vec::new();
+    generate(shell, &mut command, bin_name, &mut script);
+    let mut script = String::from_utf8(script)
+        .with_context(|| "generated completion script is not valid UTF-8")?;
+
+    match shell {
+        Shell::Bash => script.push_str(BASH_DYNAMIC_COMPLETIONS),
+        Shell::Fish => script.push_str(FISH_DYNAMIC_COMPLETIONS),
+        Shell::Zsh => {
+            // The function must be defined before the generated one is called (at the end of
+            // the script), but after the `#compdef` line, which must come first.
+            script = script
+                .replace(":TRACK:_default'", ":TRACK:_exsb_known_values track'")
+                .replace(":EXERCISE:_default'", ":EXERCISE:_exsb_known_values exercise'");
+            let insert_at = script.find('\n').map_or(0, |index| index + 1);
+            script.insert_str(insert_at, ZSH_DYNAMIC_COMPLETIONS);
+        },
+        _ => (),
+    }
+    Ok(script)
+}
+
+fn completion_values(values: CompletionValues) -> String {
+    let known_solutions = known_solutions::load();
+    let values = known_solutions
+        .iter()
+        .map(|solution| match values {
+            CompletionValues::Track => solution.track.as_str(),
+            CompletionValues::Exercise => solution.exercise.as_str(),
+        })
+        .collect::<BTreeSet<_>>();
+
+    values
+        .into_iter()
+        .map(|value| format!("{value}\n"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_script() {
+        let bash = completion_script(Shell::Bash).unwrap();
+        assert!(bash.contains("_exsb() {"));
+        assert!(bash.ends_with("complete -F _exsb_with_values -o bashdefault -o default exsb\n"));
+
+        let zsh = completion_script(Shell::Zsh).unwrap();
+        assert!(zsh.starts_with("#compdef exsb\n\n(( $+functions[_exsb_known_values] ))"));
+        assert!(zsh.contains(":TRACK:_exsb_known_values track'"));
+        assert!(zsh.contains(":EXERCISE:_exsb_known_values exercise'"));
+        assert!(!zsh.contains(":TRACK:_default'"));
+
+        let powershell = completion_script(Shell::PowerShell).unwrap();
+        assert!(!powershell.contains("_exsb_known_values"));
+    }
+}
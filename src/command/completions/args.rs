@@ -0,0 +1,26 @@
+//! Arguments that can be passed to the [`Completions`](crate::command::Command::Completions) command.
+
+use clap::{Args, ValueEnum};
+use clap_complete::Shell;
+
+/// Command-line arguments accepted by the [`Completions`](crate::command::Command::Completions) command.
+#[derive(Debug, Clone, Args)]
+pub struct CompletionsArgs {
+    /// Shell to generate completions for
+    #[arg(value_enum, required_unless_present = "values")]
+    pub shell: Option<Shell>,
+
+    /// Print known values of an argument, one per line (used by generated completion scripts)
+    #[arg(long, value_enum, hide = true, conflicts_with = "shell")]
+    pub values: Option<CompletionValues>,
+}
+
+/// Arguments whose values can be completed dynamically (see [`CompletionsArgs::values`]).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum CompletionValues {
+    /// Names of tracks with known solutions
+    Track,
+
+    /// Names of exercises with known solutions
+    Exercise,
+}
@@ -0,0 +1,319 @@
+//! Definition of the [`Daemon`](crate::command::Command::Daemon) command.
+
+pub mod args;
+
+use std::convert::Infallible;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use hyper::header::CONTENT_TYPE;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Response, Server};
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tokio::signal::ctrl_c;
+use tokio::sync::oneshot;
+use tokio::time::sleep;
+use tracing::{error, info, instrument, trace, warn};
+
+use crate::command::backup::BackupCommand;
+use crate::command::daemon::args::DaemonArgs;
+use crate::http_session::HttpSession;
+use crate::Result;
+
+/// Command wrapper used for the [`Daemon`](crate::command::Command::Daemon) command.
+///
+/// Credentials and API clients are set up once, then reused for every backup run.
+#[derive(Debug)]
+pub struct DaemonCommand {
+    args: DaemonArgs,
+    status: Mutex<DaemonStatus>,
+}
+
+/// Status of the backup runs performed by the daemon, persisted between runs in the status file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    /// Total number of backup runs.
+    pub runs: u64,
+
+    /// Total number of failed backup runs.
+    pub failures: u64,
+
+    /// Result of the last backup run, if any.
+    pub last_run: Option<RunStatus>,
+
+    /// Time the last successful backup run finished, if any (RFC 3339).
+    pub last_success: Option<String>,
+
+    /// Time of the next scheduled backup run, if any (RFC 3339).
+    pub next_run: Option<String>,
+}
+
+/// Result of a single backup run (see [`DaemonStatus::last_run`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunStatus {
+    /// Time the run started (RFC 3339).
+    pub started_at: String,
+
+    /// Time the run finished (RFC 3339).
+    pub finished_at: String,
+
+    /// Whether the backup succeeded.
+    pub success: bool,
+
+    /// Error that caused the backup to fail, if any.
+    pub error: Option<String>,
+}
+
+impl DaemonCommand {
+    /// Creates a new [`DaemonCommand`] using the provided [`args`](DaemonArgs).
+    ///
+    /// If a status file exists from a previous execution, the status is restored from it.
+    pub async fn new(args: DaemonArgs) -> Result<Arc<Self>> {
+        let status = read_status(&args.status_file())
+            .await
+            .unwrap_or_else(|error| {
+                warn!("Failed to read previous daemon status: {error:#}");
+                DaemonStatus::default()
+            });
+
+        Ok(Arc::new(Self { args, status: Mutex::new(status) }))
+    }
+
+    /// Run backups periodically, until interrupted.
+    #[instrument(skip_all)]
+    pub async fn execute(this: Arc<Self>) -> Result<()> {
+        trace!(?this.args);
+
+        // The session must outlive all backups, since API clients talk to it.
+        let session = HttpSession::start(&this.args.backup).await?;
+        let backup_command = BackupCommand::with_session(this.args.backup.clone(), session.as_ref())?;
+
+        let _status_server = match this.args.status_port {
+            Some(port) => Some(Self::serve_status(this.clone(), port)?),
+            None => None,
+        };
+
+        info!("Starting backup daemon; press Ctrl-C to stop");
+        let mut next_run = match this.args.schedule {
+            Some(_) => this.next_run_after(Utc::now()),
+            None => Some(Utc::now()),
+        };
+        let mut runs = 0;
+        while let Some(run_time) = next_run {
+            this.update_status(|status| status.next_run = Some(run_time.to_rfc3339()))
+                .await;
+            let wait = (run_time - Utc::now()).to_std().unwrap_or_default();
+            if !wait.is_zero() {
+                info!("Next backup scheduled at {}", run_time.to_rfc3339());
+            }
+            tokio::select! {
+                _ = sleep(wait) => (),
+                _ = ctrl_c() => break,
+            }
+
+            let started_at = Utc::now();
+            let result = tokio::select! {
+                result = BackupCommand::execute(backup_command.clone()) => result,
+                _ = ctrl_c() => {
+                    warn!("Backup interrupted");
+                    break;
+                },
+            };
+            this.record_run(started_at, result).await;
+
+            runs += 1;
+            if this
+                .args
+                .max_runs
+                .map_or(false, |max_runs| runs >= max_runs)
+            {
+                break;
+            }
+            next_run = this.next_run_after(started_at);
+        }
+
+        info!("Stopping backup daemon");
+        this.update_status(|status| status.next_run = None).await;
+        Ok(())
+    }
+
+    fn next_run_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match &self.args.schedule {
+            Some(schedule) => schedule.after(&time).next(),
+            None => chrono::Duration::from_std(self.args.interval)
+                .ok()
+                .and_then(|interval| time.checked_add_signed(interval)),
+        }
+    }
+
+    async fn record_run(&self, started_at: DateTime<Utc>, result: Result<()>) {
+        let finished_at = Utc::now().to_rfc3339();
+        if let Err(error) = &result {
+            error!("Backup failed: {error:#}");
+        }
+
+        self.update_status(|status| {
+            status.runs += 1;
+            if result.is_err() {
+                status.failures += 1;
+            } else {
+                status.last_success = Some(finished_at.clone());
+            }
+            status.last_run = Some(RunStatus {
+                started_at: started_at.to_rfc3339(),
+                finished_at,
+                success: result.is_ok(),
+                error: result.as_ref().err().map(|error| format!("{error:#}")),
+            });
+        })
+        .await;
+    }
+
+    async fn update_status<F>(&self, update: F)
+    where
+        F: FnOnce(&mut DaemonStatus),
+    {
+        let status = {
+            let mut status = self
+                .status
+                .lock()
+                .expect("status lock should not be poisoned");
+            update(&mut status);
+            status.clone()
+        };
+
+        let status_file = self.args.status_file();
+        if let Err(error) = write_status(&status_file, &status).await {
+            warn!("Failed to write daemon status to {}: {error:#}", status_file.display());
+        }
+    }
+
+    /// Starts a local HTTP server returning the daemon's status as JSON.
+    ///
+    /// The server stops when the returned sender is dropped.
+    fn serve_status(this: Arc<Self>, port: u16) -> Result<oneshot::Sender<()>> {
+        let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let make_service = make_service_fn(move |_| {
+            let this = this.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |_| {
+                    let status = this
+                        .status
+                        .lock()
+                        .expect("status lock should not be poisoned")
+                        .clone();
+                    async move {
+                        let body = serde_json::to_string_pretty(&status)
+                            .expect("status should be serializable");
+                        Ok::<_, Infallible>(
+                            Response::builder()
+                                .header(CONTENT_TYPE, "application/json")
+                                .body(Body::from(body))
+                                .expect("response should be valid"),
+                        )
+                    }
+                }))
+            }
+        });
+
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
+        let server = Server::try_bind(&address)
+            .with_context(|| format!("failed to listen on port {port}"))?
+            .serve(make_service)
+            .with_graceful_shutdown(async {
+                shutdown_receiver.await.ok();
+            });
+        tokio::spawn(async move {
+            if let Err(error) = server.await {
+                warn!("Daemon status server failed: {error:#}");
+            }
+        });
+
+        info!("Serving daemon status on http://{address}");
+        Ok(shutdown_sender)
+    }
+}
+
+async fn read_status(path: &Path) -> Result<DaemonStatus> {
+    if !fs::try_exists(path).await.unwrap_or(false) {
+        return Ok(DaemonStatus::default());
+    }
+
+    let content = fs::read(path)
+        .await
+        .with_context(|| format!("failed to read file {}", path.display()))?;
+    serde_json::from_slice(&content)
+        .with_context(|| format!("invalid daemon status in {}", path.display()))
+}
+
+/// Writes the status file, through a temporary file so that readers never see a partial status.
+async fn write_status(path: &Path, status: &DaemonStatus) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("failed to create directory {}", parent.display()))?;
+    }
+
+    let content =
+        serde_json::to_vec_pretty(status).with_context(|| "failed to serialize daemon status")?;
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, content)
+        .await
+        .with_context(|| format!("failed to write file {}", temp_path.display()))?;
+    fs::rename(&temp_path, path)
+        .await
+        .with_context(|| format!("failed to write file {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::command::Command;
+    use crate::Cli;
+
+    fn daemon_args(args: &[&str]) -> DaemonArgs {
+        let cli = Cli::try_parse_from(["exsb", "daemon", "backup"].iter().chain(args)).unwrap();
+        match cli.command {
+            Command::Daemon(args) => args,
+            command => panic!("unexpected command {command:?}"),
+        }
+    }
+
+    #[test]
+    fn test_next_run_after() {
+        let time = DateTime::parse_from_rfc3339("2024-01-15T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let next_run_after = |args: &[&str]| {
+            let command = DaemonCommand { args: daemon_args(args), status: Default::default() };
+            command
+                .next_run_after(time)
+                .map(|next_run| next_run.to_rfc3339())
+        };
+
+        assert_eq!(Some("2024-01-16T10:00:00+00:00".into()), next_run_after(&[]));
+        assert_eq!(Some("2024-01-15T16:00:00+00:00".into()), next_run_after(&["--interval", "6h"]));
+        assert_eq!(
+            Some("2024-01-16T03:00:00+00:00".into()),
+            next_run_after(&["--schedule", "0 0 3 * * *"])
+        );
+    }
+
+    #[test]
+    fn test_status_file() {
+        assert_eq!(
+            Path::new("backup").join(".exsb").join("daemon-status.json"),
+            daemon_args(&[]).status_file()
+        );
+        assert_eq!(
+            Path::new("status.json"),
+            daemon_args(&["--status-file", "status.json"]).status_file()
+        );
+    }
+}
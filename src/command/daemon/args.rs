@@ -0,0 +1,56 @@
+//! Arguments that can be passed to the [`Daemon`](crate::command::Command::Daemon) command.
+
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use clap::Args;
+use cron::Schedule;
+
+use crate::command::backup::args::BackupArgs;
+
+/// Command-line arguments accepted by the [`Daemon`](crate::command::Command::Daemon) command.
+#[derive(Debug, Clone, Args)]
+pub struct DaemonArgs {
+    /// Arguments used for each backup
+    #[command(flatten)]
+    pub backup: BackupArgs,
+
+    /// Time to wait between the start of two backups (e.g. `30m`, `6h` or `1day`)
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration, default_value = "1day")]
+    pub interval: Duration,
+
+    /// Cron expression determining when to run backups, in UTC (e.g. `0 0 3 * * *` for 3 AM every day)
+    ///
+    /// The expression starts with a seconds field; see the documentation of the `cron` crate for details.
+    #[arg(long, value_name = "CRON", value_parser = Schedule::from_str, conflicts_with = "interval")]
+    pub schedule: Option<Schedule>,
+
+    /// File where to write logs (appended to), instead of the standard error
+    #[arg(long, value_name = "FILE")]
+    pub log_file: Option<PathBuf>,
+
+    /// File where to write the status of backup runs, as JSON [default: .exsb/daemon-status.json in the backup]
+    #[arg(long, value_name = "FILE")]
+    pub status_file: Option<PathBuf>,
+
+    /// Serve the status of backup runs as JSON on this local port
+    #[arg(long, value_name = "PORT")]
+    pub status_port: Option<u16>,
+
+    /// Stop after this number of backup runs (only used for testing)
+    #[arg(long, hide = true)]
+    pub max_runs: Option<u64>,
+}
+
+impl DaemonArgs {
+    /// Returns the path of the file where to write the daemon's status.
+    pub fn status_file(&self) -> PathBuf {
+        self.status_file.clone().unwrap_or_else(|| {
+            self.backup
+                .path
+                .join(crate::local_backup::METADATA_DIR)
+                .join("daemon-status.json")
+        })
+    }
+}
@@ -0,0 +1,70 @@
+use anyhow::Context;
+use mini_exercism::api;
+use mini_exercism::api::v2::solution::Solution;
+use mini_exercism::api::v2::solutions;
+use tracing::debug;
+
+use crate::known_solutions;
+use crate::known_solutions::KnownSolution;
+use crate::Result;
+
+macro_rules! build_client {
+    ($client_ty:ty, $http_client:ident, $credentials:ident, $api_base_url:ident) => {{
+        let mut builder = <$client_ty>::builder();
+        builder
+            .http_client($http_client.clone())
+            .credentials($credentials.clone());
+        if let Some(api_base_url) = $api_base_url {
+            builder.api_base_url(api_base_url);
+        }
+        builder.build()
+    }};
+}
+
+/// Fetches all solutions of the account, going through every page of results.
+pub(crate) async fn get_all_solutions(v2_client: &api::v2::Client) -> Result<Vec<Solution>> {
+    let mut all_solutions = Vec::new();
+    let mut page = 1;
+    loop {
+        let response = v2_client
+            .get_solutions(None, Some(solutions::Paging::for_page(page)), None)
+            .await
+            .with_context(|| format!("failed to fetch solutions for page {page}"))?;
+        debug!("Fetched {} solution(s) in page {page}", response.results.len());
+        all_solutions.extend(response.results);
+
+        if response.meta.current_page >= response.meta.total_pages {
+            break;
+        }
+        page += 1;
+    }
+
+    known_solutions::add(all_solutions.iter().map(KnownSolution::from)).await;
+    Ok(all_solutions)
+}
+
+/// Finds the solution to an exercise in the account, going through pages of results until found.
+pub(crate) async fn find_solution(
+    v2_client: &api::v2::Client,
+    track: &str,
+    exercise: &str,
+) -> Result<Option<Solution>> {
+    let mut page = 1;
+    loop {
+        let response = v2_client
+            .get_solutions(None, Some(solutions::Paging::for_page(page)), None)
+            .await
+            .with_context(|| format!("failed to fetch solutions for page {page}"))?;
+        debug!("Fetched {} solution(s) in page {page}", response.results.len());
+        known_solutions::add(response.results.iter().map(KnownSolution::from)).await;
+
+        let solution = response
+            .results
+            .into_iter()
+            .find(|solution| solution.track.name == track && solution.exercise.name == exercise);
+        if solution.is_some() || response.meta.current_page >= response.meta.total_pages {
+            return Ok(solution);
+        }
+        page += 1;
+    }
+}
@@ -0,0 +1,48 @@
+//! Definition of the [`Dev`](crate::command::Command::Dev) command.
+
+pub mod args;
+
+use std::net::{Ipv4Addr, TcpListener};
+
+use anyhow::Context;
+use tokio::signal::ctrl_c;
+use tracing::{info, instrument};
+
+use crate::command::dev::args::{DevAction, DevArgs, FakeServerArgs};
+use crate::fake_server::FakeServer;
+use crate::Result;
+
+/// Command wrapper used for the hidden [`Dev`](crate::command::Command::Dev) command.
+#[derive(Debug)]
+pub struct DevCommand {
+    args: DevArgs,
+}
+
+impl DevCommand {
+    /// Creates a new [`DevCommand`] using the provided [`args`](DevArgs).
+    pub fn new(args: DevArgs) -> Self {
+        Self { args }
+    }
+
+    /// Execute the development tool.
+    #[instrument(skip_all)]
+    pub async fn execute(&self) -> Result<()> {
+        match &self.args.action {
+            DevAction::FakeServer(args) => Self::fake_server(args).await,
+        }
+    }
+
+    async fn fake_server(args: &FakeServerArgs) -> Result<()> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, args.port))
+            .with_context(|| format!("failed to listen on port {}", args.port))?;
+        let server = FakeServer::start_with_listener(&args.fixtures, Some(listener)).await?;
+
+        info!("Fake Exercism API server listening on {}", server.uri());
+        info!(
+            "Use `--api-base-url {}` to point other commands to it; press Ctrl-C to stop",
+            server.uri()
+        );
+
+        ctrl_c().await.with_context(|| "failed to wait for Ctrl-C")
+    }
+}
@@ -0,0 +1,34 @@
+//! Arguments that can be passed to the [`Dev`](crate::command::Command::Dev) command.
+
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+
+/// Command-line arguments accepted by the [`Dev`](crate::command::Command::Dev) command.
+#[derive(Debug, Clone, Args)]
+pub struct DevArgs {
+    /// Development tool to run.
+    #[command(subcommand)]
+    pub action: DevAction,
+}
+
+/// Possible actions of the [`Dev`](crate::command::Command::Dev) command.
+#[derive(Debug, Clone, Subcommand)]
+pub enum DevAction {
+    /// Start a fake Exercism API server serving accounts from a fixtures directory
+    ///
+    /// See the `exsb::fake_server` module documentation for the layout of the fixtures directory.
+    /// Once started, pass the server's URL to other commands via --api-base-url.
+    FakeServer(FakeServerArgs),
+}
+
+/// Command-line arguments accepted by [`DevAction::FakeServer`].
+#[derive(Debug, Clone, Args)]
+pub struct FakeServerArgs {
+    /// Path of the fixtures directory containing fake accounts
+    pub fixtures: PathBuf,
+
+    /// Local port to listen on (if unspecified, a random port is used)
+    #[arg(short, long, default_value_t = 0)]
+    pub port: u16,
+}
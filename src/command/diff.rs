@@ -0,0 +1,159 @@
+//! Definition of the [`Diff`](crate::command::Command::Diff) command.
+
+pub mod args;
+
+use anyhow::{anyhow, Context};
+use futures::StreamExt;
+use mini_exercism::api;
+use tracing::{debug, info, instrument, trace};
+
+use crate::cache::HttpCache;
+use crate::command::detail::find_solution;
+use crate::command::diff::args::{DiffArgs, IterationRef};
+use crate::credentials::{resolve_credentials, validate_credentials};
+use crate::file_diff::{diff_files, FileDiff};
+use crate::local_backup::{read_solution, LocalFile, LocalSolution};
+use crate::local_server::LocalServer;
+use crate::Result;
+
+/// Command wrapper used for the [`Diff`](crate::command::Command::Diff) command.
+#[derive(Debug)]
+pub struct DiffCommand {
+    args: DiffArgs,
+}
+
+impl DiffCommand {
+    /// Creates a new [`DiffCommand`] using the provided [`args`](DiffArgs).
+    pub fn new(args: DiffArgs) -> Self {
+        Self { args }
+    }
+
+    /// Compare the solution and print differences to standard output.
+    #[instrument(skip_all)]
+    pub async fn execute(&self) -> Result<()> {
+        trace!(?self.args);
+
+        let cipher = self
+            .args
+            .encryption
+            .cipher()
+            .with_context(|| "failed to initialize decryption")?;
+        let solution =
+            read_solution(&self.args.path, &self.args.track, &self.args.exercise, cipher.as_ref())
+                .await?;
+
+        let (old, new) = match self.args.iterations.as_slice() {
+            [old, new] => {
+                info!("Comparing {old} and {new} of {}/{}", solution.track, solution.exercise);
+                (iteration_files(&solution, *old)?, iteration_files(&solution, *new)?)
+            },
+            _ => {
+                info!(
+                    "Comparing local backup of {}/{} with its latest iteration on Exercism",
+                    solution.track, solution.exercise
+                );
+                (solution.files.clone(), self.remote_files(&solution).await?)
+            },
+        };
+
+        let changes = diff_files(&old, &new);
+        if changes.is_empty() {
+            info!("No differences found");
+        }
+        for change in changes {
+            match change.diff {
+                FileDiff::Text(diff) => print!("{diff}"),
+                FileDiff::Binary => println!("Binary file {} differs", change.path),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Downloads the files of the latest iteration of the solution from Exercism.
+    async fn remote_files(&self, solution: &LocalSolution) -> Result<Vec<LocalFile>> {
+        let (credentials, source) = resolve_credentials(&self.args.credentials)?;
+        let upstream_client = self.args.http.build_http_client()?;
+        let cache = HttpCache::start(
+            &self.args.cache,
+            upstream_client.clone(),
+            self.args.api_base_url.as_deref(),
+        )
+        .await?;
+        let (http_client, api_base_url) = match &cache {
+            Some(cache) => (LocalServer::http_client()?, Some(cache.uri())),
+            None => (upstream_client, self.args.api_base_url.clone()),
+        };
+        let api_base_url = api_base_url.as_deref();
+        let v1_client = build_client!(api::v1::Client, http_client, credentials, api_base_url);
+        let v2_client = build_client!(api::v2::Client, http_client, credentials, api_base_url);
+
+        validate_credentials(&v1_client, &source).await?;
+
+        let uuid = match &solution.metadata {
+            Some(metadata) => metadata.uuid.clone(),
+            None => {
+                debug!("Solution has no metadata; looking it up on Exercism");
+                find_solution(&v2_client, &solution.track, &solution.exercise)
+                    .await?
+                    .map(|remote| remote.uuid)
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "no solution to {}/{} found on Exercism",
+                            solution.track,
+                            solution.exercise
+                        )
+                    })?
+            },
+        };
+
+        let file_names = v1_client
+            .get_solution(&uuid)
+            .await
+            .with_context(|| {
+                format!(
+                    "failed to get solution to {}/{} from Exercism",
+                    solution.track, solution.exercise
+                )
+            })?
+            .solution
+            .files;
+
+        let mut files = Vec::new();
+        for file_name in file_names {
+            trace!(file_name, "Downloading file");
+            let mut content = Vec::new();
+            let mut file_stream = v1_client.get_file(&uuid, &file_name).await;
+            while let Some(bytes) = file_stream.next().await {
+                let bytes = bytes.with_context(|| {
+                    format!(
+                        "failed to download file {} in solution to exercise {}/{}",
+                        file_name, solution.track, solution.exercise,
+                    )
+                })?;
+                content.extend_from_slice(&bytes);
+            }
+            files.push(LocalFile { relative_path: file_name.split('/').collect(), content });
+        }
+
+        Ok(files)
+    }
+}
+
+fn iteration_files(solution: &LocalSolution, iteration: IterationRef) -> Result<Vec<LocalFile>> {
+    match iteration {
+        IterationRef::Latest => Ok(solution.files.clone()),
+        IterationRef::Number(number) => solution
+            .iterations
+            .iter()
+            .find(|local_iteration| local_iteration.number == number)
+            .map(|local_iteration| local_iteration.files.clone())
+            .ok_or_else(|| {
+                anyhow!(
+                    "iteration {number} of {}/{} is not in the backup (see `backup --keep-iterations`)",
+                    solution.track,
+                    solution.exercise
+                )
+            }),
+    }
+}
@@ -0,0 +1,93 @@
+//! Arguments that can be passed to the [`Diff`](crate::command::Command::Diff) command.
+
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use clap::Args;
+
+use crate::command::args::{CacheArgs, CredentialsArgs, EncryptionArgs, HttpClientArgs};
+
+/// Command-line arguments accepted by the [`Diff`](crate::command::Command::Diff) command.
+#[derive(Debug, Clone, Args)]
+pub struct DiffArgs {
+    /// Path of the backup containing the solution
+    pub path: PathBuf,
+
+    /// Name of the solution's track
+    pub track: String,
+
+    /// Name of the solution's exercise
+    pub exercise: String,
+
+    /// Compare two iterations of the local backup instead of comparing it with Exercism (use
+    /// iteration numbers, or `latest` for the latest backed-up iteration)
+    #[arg(long, num_args = 2, value_names = ["OLD", "NEW"])]
+    pub iterations: Vec<IterationRef>,
+
+    /// Exercism.org API token to use (not used with --iterations)
+    #[command(flatten)]
+    pub credentials: CredentialsArgs,
+
+    /// Configuration of the HTTP client used to access the Exercism API
+    #[command(flatten)]
+    pub http: HttpClientArgs,
+
+    /// Configuration of the cache of Exercism API responses
+    #[command(flatten)]
+    pub cache: CacheArgs,
+
+    /// Base URL of the Exercism API (only used for testing, see `exsb dev fake-server`)
+    #[arg(long, hide = true)]
+    pub api_base_url: Option<String>,
+
+    /// Key used to decrypt the backed-up files, if the backup is encrypted
+    #[command(flatten)]
+    pub encryption: EncryptionArgs,
+}
+
+/// Reference to an iteration of a backed-up solution (see [`DiffArgs::iterations`]).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IterationRef {
+    /// Previous iteration kept with `backup --keep-iterations`
+    Number(u32),
+
+    /// Latest backed-up iteration
+    Latest,
+}
+
+impl FromStr for IterationRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "latest" => Ok(Self::Latest),
+            _ => s
+                .parse()
+                .map(Self::Number)
+                .map_err(|_| format!("invalid iteration {s}: expected a number or `latest`")),
+        }
+    }
+}
+
+impl fmt::Display for IterationRef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(number) => write!(f, "iteration {number}"),
+            Self::Latest => write!(f, "latest iteration"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iteration_ref_from_str() {
+        assert_eq!(Ok(IterationRef::Number(2)), "2".parse());
+        assert_eq!(Ok(IterationRef::Latest), "latest".parse());
+        assert!("first".parse::<IterationRef>().is_err());
+    }
+}
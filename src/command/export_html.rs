@@ -0,0 +1,65 @@
+//! Definition of the [`ExportHtml`](crate::command::Command::ExportHtml) command.
+
+pub mod args;
+
+use anyhow::Context;
+use tokio::fs;
+use tracing::{info, instrument, trace};
+
+use crate::command::export_html::args::ExportHtmlArgs;
+use crate::html_site::render_site;
+use crate::local_backup::read_solutions;
+use crate::Result;
+
+/// Command wrapper used for the [`ExportHtml`](crate::command::Command::ExportHtml) command.
+#[derive(Debug)]
+pub struct ExportHtmlCommand {
+    args: ExportHtmlArgs,
+}
+
+impl ExportHtmlCommand {
+    /// Creates a new [`ExportHtmlCommand`] using the provided [`args`](ExportHtmlArgs).
+    pub fn new(args: ExportHtmlArgs) -> Self {
+        Self { args }
+    }
+
+    /// Execute the export operation.
+    #[instrument(skip_all)]
+    pub async fn execute(&self) -> Result<()> {
+        info!(
+            "Exporting backup {} as HTML site to {}",
+            self.args.path.display(),
+            self.args.output.display()
+        );
+        trace!(?self.args);
+
+        let cipher = self
+            .args
+            .encryption
+            .cipher()
+            .with_context(|| "failed to initialize decryption")?;
+        let solutions = read_solutions(&self.args.path, cipher.as_ref())
+            .await
+            .with_context(|| format!("failed to read backup {}", self.args.path.display()))?;
+
+        let pages = render_site(&solutions, false);
+        for page in &pages {
+            let page_path = self.args.output.join(&page.path);
+            if let Some(parent) = page_path.parent() {
+                fs::create_dir_all(parent)
+                    .await
+                    .with_context(|| format!("failed to create directory {}", parent.display()))?;
+            }
+            fs::write(&page_path, &page.content)
+                .await
+                .with_context(|| format!("failed to write file {}", page_path.display()))?;
+        }
+
+        info!(
+            "Exported {} solution(s); open {} to browse them",
+            solutions.len(),
+            self.args.output.join("index.html").display()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+//! Arguments that can be passed to the [`ExportHtml`](crate::command::Command::ExportHtml) command.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::command::args::EncryptionArgs;
+
+/// Command-line arguments accepted by the [`ExportHtml`](crate::command::Command::ExportHtml) command.
+#[derive(Debug, Clone, Args)]
+pub struct ExportHtmlArgs {
+    /// Path of the backup to export
+    pub path: PathBuf,
+
+    /// Path where to store the generated site
+    pub output: PathBuf,
+
+    /// Key used to decrypt the backed-up files, if the backup is encrypted
+    #[command(flatten)]
+    pub encryption: EncryptionArgs,
+}
@@ -0,0 +1,93 @@
+//! Definition of the [`Man`](crate::command::Command::Man) command.
+
+pub mod args;
+
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::Context;
+use clap::CommandFactory;
+use clap_mangen::Man;
+use tokio::fs;
+use tracing::{info, instrument, trace};
+
+use crate::command::man::args::ManArgs;
+use crate::{Cli, Result};
+
+/// Command wrapper used for the [`Man`](crate::command::Command::Man) command.
+#[derive(Debug)]
+pub struct ManCommand {
+    args: ManArgs,
+}
+
+impl ManCommand {
+    /// Creates a new [`ManCommand`] using the provided [`args`](ManArgs).
+    pub fn new(args: ManArgs) -> Self {
+        Self { args }
+    }
+
+    /// Generate man pages.
+    #[instrument(skip_all)]
+    pub async fn execute(&self) -> Result<()> {
+        trace!(?self.args);
+
+        let command = Cli::command();
+        match &self.args.output {
+            Some(output) => {
+                fs::create_dir_all(output).await.with_context(|| {
+                    format!("failed to create output directory {}", output.display())
+                })?;
+                let num_pages = write_man_pages(command, output).await?;
+                info!("Wrote {num_pages} man page(s) to {}", output.display());
+                Ok(())
+            },
+            None => std::io::stdout()
+                .write_all(&render_man_page(command)?)
+                .with_context(|| "failed to write to standard output"),
+        }
+    }
+}
+
+/// Writes man pages for `command` and its (visible) subcommands, named like `exsb-backup.1`.
+///
+/// Pages are not generated for `help` subcommands, since they are covered by their parent's page.
+///
+/// Returns the number of pages written.
+async fn write_man_pages(command: clap::Command, output: &Path) -> Result<usize> {
+    let mut num_pages = 0;
+    let mut commands = vec![(command.get_name().to_string(), command)];
+
+    while let Some((name, mut command)) = commands.pop() {
+        // Building the command sets display names of subcommands (like `exsb-backup`).
+        command.build();
+        commands.extend(
+            command
+                .get_subcommands()
+                .filter(|subcommand| !subcommand.is_hide_set() && subcommand.get_name() != "help")
+                .map(|subcommand| {
+                    let name = subcommand
+                        .get_display_name()
+                        .unwrap_or_else(|| subcommand.get_name())
+                        .to_string();
+                    (name, subcommand.clone())
+                }),
+        );
+
+        let path = output.join(format!("{name}.1"));
+        trace!(path = %path.display(), "Writing man page");
+        fs::write(&path, render_man_page(command)?)
+            .await
+            .with_context(|| format!("failed to write file {}", path.display()))?;
+        num_pages += 1;
+    }
+
+    Ok(num_pages)
+}
+
+fn render_man_page(command: clap::Command) -> Result<Vec<u8>> {
+    let mut page = Vec::new();
+    Man::new(command)
+        .render(&mut page)
+        .with_context(|| "failed to render man page")?;
+    Ok(page)
+}
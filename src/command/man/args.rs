@@ -0,0 +1,14 @@
+//! Arguments that can be passed to the [`Man`](crate::command::Command::Man) command.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+/// Command-line arguments accepted by the [`Man`](crate::command::Command::Man) command.
+#[derive(Debug, Clone, Args)]
+pub struct ManArgs {
+    /// Directory where to write man pages for exsb and all its commands (by default, the main
+    /// man page is printed to standard output)
+    #[arg(long, value_name = "DIR")]
+    pub output: Option<PathBuf>,
+}
@@ -0,0 +1,260 @@
+//! Definition of the [`Prune`](crate::command::Command::Prune) command.
+
+pub mod args;
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use tokio::fs;
+use tracing::{info, instrument, trace};
+
+use crate::command::backup::account::{read_accounts_file, Account};
+use crate::command::detail::get_all_solutions;
+use crate::command::prune::args::PruneArgs;
+use crate::credentials::{resolve_credentials, validate_credentials, CredentialSource};
+use crate::local_backup::list_subdirectories;
+use crate::Result;
+
+/// Command wrapper used for the [`Prune`](crate::command::Command::Prune) command.
+#[derive(Debug)]
+pub struct PruneCommand {
+    args: PruneArgs,
+}
+
+impl PruneCommand {
+    /// Creates a new [`PruneCommand`] using the provided [`args`](PruneArgs).
+    pub fn new(args: PruneArgs) -> Self {
+        Self { args }
+    }
+
+    /// Execute the prune operation.
+    ///
+    /// If an [`accounts_file`](PruneArgs::accounts_file) is specified, the subdirectory of each
+    /// account found in the file is pruned, using the account's own token.
+    #[instrument(skip_all)]
+    pub async fn execute(&self) -> Result<()> {
+        info!("Pruning Exercism solutions backup in {}", self.args.path.display());
+        trace!(?self.args);
+
+        let http_client = self.args.http.build_http_client()?;
+        let api_base_url = self.args.api_base_url.as_deref();
+        let accounts = match &self.args.accounts_file {
+            Some(accounts_file) => read_accounts_file(accounts_file)?
+                .into_iter()
+                .map(|(name, credentials)| {
+                    let credential_source = CredentialSource::AccountsFile {
+                        path: accounts_file.clone(),
+                        account: name.clone(),
+                    };
+                    Account::new(
+                        Some(name),
+                        &http_client,
+                        &credentials,
+                        credential_source,
+                        api_base_url,
+                    )
+                })
+                .collect(),
+            None => {
+                let (credentials, credential_source) = resolve_credentials(&self.args.credentials)?;
+                vec![Account::new(
+                    None,
+                    &http_client,
+                    &credentials,
+                    credential_source,
+                    api_base_url,
+                )]
+            },
+        };
+
+        for account in &accounts {
+            self.prune_account(account).await?;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(account = account.display_name()))]
+    async fn prune_account(&self, account: &Account) -> Result<()> {
+        let (root, quarantine) = match &account.name {
+            Some(name) => (
+                self.args.path.join(name),
+                self.args
+                    .quarantine
+                    .as_ref()
+                    .map(|quarantine| quarantine.join(name)),
+            ),
+            None => (self.args.path.clone(), self.args.quarantine.clone()),
+        };
+
+        let expected = self.expected_solutions(account).await?;
+        let local = read_backup_tree(&root).await?;
+        let orphans = find_orphans(&local, &expected);
+        if orphans.is_empty() {
+            info!("Nothing to prune");
+            return Ok(());
+        }
+
+        for orphan in &orphans {
+            let path = orphan.path(&root);
+            match (&quarantine, self.args.dry_run) {
+                (Some(_), true) => info!("Would quarantine {orphan}"),
+                (None, true) => info!("Would remove {orphan}"),
+                (Some(quarantine), false) => {
+                    let destination = orphan.path(quarantine);
+                    trace!(destination = %destination.display());
+                    quarantine_directory(&path, &destination).await?;
+                    info!("Quarantined {orphan} to {}", destination.display());
+                },
+                (None, false) => {
+                    fs::remove_dir_all(&path).await.with_context(|| {
+                        format!("failed to remove directory {}", path.display())
+                    })?;
+                    info!("Removed {orphan}");
+                },
+            }
+        }
+
+        match self.args.dry_run {
+            true => info!("Would prune {} orphaned track or exercise directories", orphans.len()),
+            false => info!("Pruned {} orphaned track or exercise directories", orphans.len()),
+        }
+        Ok(())
+    }
+
+    /// Returns the solutions of an account that would be backed up, as `(track, exercise)` pairs.
+    ///
+    /// Fails if the account has no solutions at all: this is more likely to be a problem with the
+    /// API than an account whose backup should be emptied.
+    async fn expected_solutions(&self, account: &Account) -> Result<HashSet<(String, String)>> {
+        validate_credentials(&account.v1_client, &account.credential_source).await?;
+
+        let solutions = get_all_solutions(&account.v2_client).await?;
+        if solutions.is_empty() {
+            return Err(anyhow!(
+                "no solutions found on Exercism using the API token from {}; refusing to prune \
+                the entire backup",
+                account.credential_source
+            ));
+        }
+
+        Ok(solutions
+            .into_iter()
+            .filter(|solution| {
+                self.args.filter.matches(
+                    &solution.track.name,
+                    &solution.exercise.name,
+                    solution.status.try_into().ok(),
+                )
+            })
+            .map(|solution| (solution.track.name, solution.exercise.name))
+            .collect())
+    }
+}
+
+/// Directory of a backup that does not correspond to a solution to back up anymore.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Orphan {
+    /// Track directory without any solution to back up.
+    Track(String),
+
+    /// Exercise directory in a track that still has solutions to back up.
+    Exercise(String, String),
+}
+
+impl Orphan {
+    fn path(&self, root: &Path) -> PathBuf {
+        match self {
+            Self::Track(track) => root.join(track),
+            Self::Exercise(track, exercise) => root.join(track).join(exercise),
+        }
+    }
+}
+
+impl fmt::Display for Orphan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Track(track) => write!(f, "track {track}"),
+            Self::Exercise(track, exercise) => write!(f, "exercise {track}/{exercise}"),
+        }
+    }
+}
+
+/// Lists the exercise directories of each track directory of the backup at `root`.
+async fn read_backup_tree(root: &Path) -> Result<BTreeMap<String, Vec<String>>> {
+    let mut tree = BTreeMap::new();
+    for track in list_subdirectories(root).await? {
+        let exercises = list_subdirectories(&root.join(&track)).await?;
+        tree.insert(track, exercises);
+    }
+
+    Ok(tree)
+}
+
+fn find_orphans(
+    local: &BTreeMap<String, Vec<String>>,
+    expected: &HashSet<(String, String)>,
+) -> Vec<Orphan> {
+    let expected_tracks = expected
+        .iter()
+        .map(|(track, _)| track.as_str())
+        .collect::<HashSet<_>>();
+
+    local
+        .iter()
+        .flat_map(|(track, exercises)| {
+            if !expected_tracks.contains(track.as_str()) {
+                return vec![Orphan::Track(track.clone())];
+            }
+            exercises
+                .iter()
+                .filter(|exercise| !expected.contains(&(track.clone(), (*exercise).clone())))
+                .map(|exercise| Orphan::Exercise(track.clone(), exercise.clone()))
+                .collect()
+        })
+        .collect()
+}
+
+async fn quarantine_directory(path: &Path, destination: &Path) -> Result<()> {
+    if fs::try_exists(destination).await.unwrap_or(false) {
+        return Err(anyhow!(
+            "cannot quarantine {}: {} already exists",
+            path.display(),
+            destination.display()
+        ));
+    }
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("failed to create directory {}", parent.display()))?;
+    }
+
+    fs::rename(path, destination)
+        .await
+        .with_context(|| format!("failed to move {} to {}", path.display(), destination.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_orphans() {
+        let local = BTreeMap::from([
+            ("elixir".to_string(), vec!["two-fer".to_string()]),
+            ("rust".to_string(), vec!["hello-world".to_string(), "poker".to_string()]),
+        ]);
+        let expected = HashSet::from([("rust".to_string(), "poker".to_string())]);
+
+        assert_eq!(
+            vec![
+                Orphan::Track("elixir".into()),
+                Orphan::Exercise("rust".into(), "hello-world".into()),
+            ],
+            find_orphans(&local, &expected)
+        );
+    }
+}
@@ -0,0 +1,47 @@
+//! Arguments that can be passed to the [`Prune`](crate::command::Command::Prune) command.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::command::args::{CredentialsArgs, HttpClientArgs, SolutionFilterArgs};
+
+/// Command-line arguments accepted by the [`Prune`](crate::command::Command::Prune) command.
+#[derive(Debug, Clone, Args)]
+pub struct PruneArgs {
+    /// Path of the backup to prune
+    pub path: PathBuf,
+
+    /// Exercism.org API token to use
+    #[command(flatten)]
+    pub credentials: CredentialsArgs,
+
+    /// File containing named tokens of multiple accounts (one `name = token` per line)
+    ///
+    /// Use this to prune a backup made with `backup --accounts-file`: each account's
+    /// subdirectory of the backup is pruned.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["token", "token_file"])]
+    pub accounts_file: Option<PathBuf>,
+
+    /// Configuration of the HTTP client used to access the Exercism API
+    #[command(flatten)]
+    pub http: HttpClientArgs,
+
+    /// Base URL of the Exercism API (only used for testing, see `exsb dev fake-server`)
+    #[arg(long, hide = true)]
+    pub api_base_url: Option<String>,
+
+    /// Filters used to select solutions to keep (solutions not matching them are pruned)
+    #[command(flatten)]
+    pub filter: SolutionFilterArgs,
+
+    /// Move orphaned directories to this directory instead of deleting them
+    ///
+    /// With --accounts-file, each account's directories are moved to a subdirectory of this directory.
+    #[arg(long, value_name = "DIR")]
+    pub quarantine: Option<PathBuf>,
+
+    /// Determine what directories to prune without removing them
+    #[arg(long, default_value_t = false)]
+    pub dry_run: bool,
+}
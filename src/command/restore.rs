@@ -0,0 +1,104 @@
+//! Definition of the [`Restore`](crate::command::Command::Restore) command.
+
+pub mod args;
+
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
+use tokio::fs;
+use tracing::{debug, info, instrument, trace};
+
+use crate::command::restore::args::RestoreArgs;
+use crate::crypto::Cipher;
+use crate::error::MultiError;
+use crate::local_backup::{list_files, CHECKSUMS_FILE, METADATA_DIR};
+use crate::Result;
+
+/// Command wrapper used for the [`Restore`](crate::command::Command::Restore) command.
+///
+/// Restoring a backup decrypts all files that were encrypted by the
+/// [`Backup`](crate::command::Command::Backup) command. Files that are not encrypted are
+/// copied as-is.
+#[derive(Debug)]
+pub struct RestoreCommand {
+    args: RestoreArgs,
+    cipher: Cipher,
+}
+
+impl RestoreCommand {
+    /// Creates a new [`RestoreCommand`] using the provided [`args`](RestoreArgs).
+    pub fn new(args: RestoreArgs) -> Result<Self> {
+        let cipher = args
+            .encryption
+            .cipher()
+            .with_context(|| "failed to initialize decryption")?
+            .ok_or_else(|| {
+                anyhow!("an encryption key file or passphrase is needed to restore a backup")
+            })?;
+
+        Ok(Self { args, cipher })
+    }
+
+    /// Execute the restore operation.
+    #[instrument(skip_all)]
+    pub async fn execute(&self) -> Result<()> {
+        match &self.args.output {
+            Some(output) if !self.args.check => {
+                info!("Restoring backup {} to {}", self.args.path.display(), output.display())
+            },
+            _ => info!("Verifying backup {}", self.args.path.display()),
+        }
+        trace!(?self.args);
+
+        // Checksums are those of the encrypted files, so they would not match restored files.
+        let checksums_file = Path::new(METADATA_DIR).join(CHECKSUMS_FILE);
+        let files = list_files(&self.args.path)
+            .await?
+            .into_iter()
+            .filter(|file| !file.ends_with(&checksums_file))
+            .collect::<Vec<_>>();
+        let mut errors = Vec::new();
+        for file in &files {
+            if let Err(error) = self.restore_file(file).await {
+                errors.push(error);
+            }
+        }
+
+        MultiError::check(errors, || "errors detected while restoring backup")?;
+
+        info!("{} file(s) successfully restored", files.len());
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    async fn restore_file(&self, relative_path: &Path) -> Result<()> {
+        let source_path = self.args.path.join(relative_path);
+        let data = fs::read(&source_path)
+            .await
+            .with_context(|| format!("failed to read file {}", source_path.display()))?;
+
+        let data = if Cipher::is_encrypted(&data) {
+            self.cipher
+                .decrypt(&data)
+                .with_context(|| format!("failed to decrypt file {}", source_path.display()))?
+        } else {
+            debug!("File is not encrypted; copying as-is");
+            data
+        };
+
+        match (&self.args.output, self.args.check) {
+            (Some(output), false) => {
+                let destination_path = output.join(relative_path);
+                if let Some(parent) = destination_path.parent() {
+                    fs::create_dir_all(parent).await.with_context(|| {
+                        format!("failed to create directory {}", parent.display())
+                    })?;
+                }
+                fs::write(&destination_path, data)
+                    .await
+                    .with_context(|| format!("failed to write file {}", destination_path.display()))
+            },
+            _ => Ok(()),
+        }
+    }
+}
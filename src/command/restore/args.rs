@@ -0,0 +1,26 @@
+//! Arguments that can be passed to the [`Restore`](crate::command::Command::Restore) command.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::command::args::EncryptionArgs;
+
+/// Command-line arguments accepted by the [`Restore`](crate::command::Command::Restore) command.
+#[derive(Debug, Clone, Args)]
+pub struct RestoreArgs {
+    /// Path of the encrypted backup
+    pub path: PathBuf,
+
+    /// Path where to store the decrypted files
+    #[arg(required_unless_present = "check")]
+    pub output: Option<PathBuf>,
+
+    /// Only verify that all files can be decrypted, without writing anything
+    #[arg(long, default_value_t = false)]
+    pub check: bool,
+
+    /// Key used to decrypt the backed-up files
+    #[command(flatten)]
+    pub encryption: EncryptionArgs,
+}
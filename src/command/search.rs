@@ -0,0 +1,165 @@
+//! Definition of the [`Search`](crate::command::Command::Search) command.
+
+pub mod args;
+
+use std::fmt::Write;
+
+use anyhow::Context;
+use regex::RegexBuilder;
+use tracing::{info, instrument, trace};
+
+use crate::command::search::args::{SearchArgs, SearchFormat};
+use crate::local_backup::read_solutions;
+use crate::search::{search, FileMatches, SearchOptions};
+use crate::Result;
+
+/// Command wrapper used for the [`Search`](crate::command::Command::Search) command.
+#[derive(Debug)]
+pub struct SearchCommand {
+    args: SearchArgs,
+}
+
+impl SearchCommand {
+    /// Creates a new [`SearchCommand`] using the provided [`args`](SearchArgs).
+    pub fn new(args: SearchArgs) -> Self {
+        Self { args }
+    }
+
+    /// Search the backup and print matches to standard output.
+    #[instrument(skip_all)]
+    pub async fn execute(&self) -> Result<()> {
+        trace!(?self.args);
+
+        let regex = RegexBuilder::new(&self.args.pattern)
+            .case_insensitive(self.args.ignore_case)
+            .build()
+            .with_context(|| format!("invalid search pattern {}", self.args.pattern))?;
+        let cipher = self
+            .args
+            .encryption
+            .cipher()
+            .with_context(|| "failed to initialize decryption")?;
+
+        let mut solutions = read_solutions(&self.args.path, cipher.as_ref())
+            .await
+            .with_context(|| format!("failed to read backup {}", self.args.path.display()))?;
+        solutions.retain(|solution| {
+            let status = solution
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.status.try_into().ok());
+            self.args
+                .filter
+                .matches(&solution.track, &solution.exercise, status)
+        });
+
+        let options =
+            SearchOptions { context: self.args.context, all_iterations: self.args.all_iterations };
+        let matches = search(&solutions, &regex, options);
+
+        match self.args.format {
+            SearchFormat::Text => {
+                if matches.is_empty() {
+                    info!("No matches found");
+                }
+                print!("{}", to_text(&matches));
+            },
+            SearchFormat::Json => {
+                let json = serde_json::to_string_pretty(&matches)
+                    .with_context(|| "failed to serialize search results")?;
+                println!("{json}");
+            },
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders matches grouped by solution, in a format similar to `grep`: matching lines are
+/// prefixed with `path:line:`, context lines with `path-line-`.
+fn to_text(matches: &[FileMatches]) -> String {
+    let mut output = String::new();
+    let mut current_solution = None;
+
+    for file_matches in matches {
+        let solution = (&file_matches.track, &file_matches.exercise, file_matches.iteration);
+        if current_solution != Some(solution) {
+            if current_solution.is_some() {
+                output.push('\n');
+            }
+            match file_matches.iteration {
+                Some(iteration) => writeln!(
+                    output,
+                    "{}/{} (iteration {iteration})",
+                    file_matches.track, file_matches.exercise
+                ),
+                None => writeln!(output, "{}/{}", file_matches.track, file_matches.exercise),
+            }
+            .expect("writing to a String should not fail");
+            current_solution = Some(solution);
+        } else {
+            output.push_str("--\n");
+        }
+
+        let mut previous_number = None;
+        for line in &file_matches.lines {
+            if previous_number.map_or(false, |previous| line.number > previous + 1) {
+                output.push_str("--\n");
+            }
+            let separator = if line.is_match { ':' } else { '-' };
+            writeln!(
+                output,
+                "{}{separator}{}{separator}{}",
+                file_matches.path, line.number, line.text
+            )
+            .expect("writing to a String should not fail");
+            previous_number = Some(line.number);
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::SearchLine;
+
+    #[test]
+    fn test_to_text() {
+        let line =
+            |number, is_match| SearchLine { number, text: format!("line {number}"), is_match };
+        let file_matches = |exercise: &str, iteration, path: &str, lines| FileMatches {
+            track: "rust".into(),
+            exercise: exercise.into(),
+            iteration,
+            path: path.into(),
+            lines,
+        };
+
+        let text = to_text(&[
+            file_matches(
+                "poker",
+                None,
+                "src/lib.rs",
+                vec![line(1, true), line(2, false), line(9, true)],
+            ),
+            file_matches("poker", None, "tests/poker.rs", vec![line(3, true)]),
+            file_matches("poker", Some(1), "src/lib.rs", vec![line(4, true)]),
+        ]);
+
+        assert_eq!(
+            "rust/poker\n\
+            src/lib.rs:1:line 1\n\
+            src/lib.rs-2-line 2\n\
+            --\n\
+            src/lib.rs:9:line 9\n\
+            --\n\
+            tests/poker.rs:3:line 3\n\
+            \n\
+            rust/poker (iteration 1)\n\
+            src/lib.rs:4:line 4\n",
+            text
+        );
+    }
+}
@@ -0,0 +1,52 @@
+//! Arguments that can be passed to the [`Search`](crate::command::Command::Search) command.
+
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+
+use crate::command::args::{EncryptionArgs, SolutionFilterArgs};
+
+/// Command-line arguments accepted by the [`Search`](crate::command::Command::Search) command.
+#[derive(Debug, Clone, Args)]
+pub struct SearchArgs {
+    /// Regular expression to search for
+    pub pattern: String,
+
+    /// Path of the backup to search
+    #[arg(default_value = ".")]
+    pub path: PathBuf,
+
+    /// Filters used to select solutions to search
+    #[command(flatten)]
+    pub filter: SolutionFilterArgs,
+
+    /// Search case-insensitively
+    #[arg(short, long, default_value_t = false)]
+    pub ignore_case: bool,
+
+    /// Number of lines to show before and after each match
+    #[arg(short = 'C', long, value_name = "LINES", default_value_t = 2)]
+    pub context: usize,
+
+    /// Also search previous iterations of solutions (see `backup --keep-iterations`)
+    #[arg(long, default_value_t = false)]
+    pub all_iterations: bool,
+
+    /// Format used to output matches
+    #[arg(long, value_enum, default_value_t = SearchFormat::Text)]
+    pub format: SearchFormat,
+
+    /// Key used to decrypt the backed-up files, if the backup is encrypted
+    #[command(flatten)]
+    pub encryption: EncryptionArgs,
+}
+
+/// Possible output formats for search results (see [`SearchArgs::format`]).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum SearchFormat {
+    /// Matches grouped by solution, with context lines
+    Text,
+
+    /// JSON array containing one object per file with matches
+    Json,
+}
@@ -0,0 +1,148 @@
+//! Definition of the [`Serve`](crate::command::Command::Serve) command.
+
+pub mod args;
+
+use std::convert::Infallible;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+use anyhow::Context;
+use hyper::header::CONTENT_TYPE;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use percent_encoding::percent_decode_str;
+use regex::RegexBuilder;
+use tokio::signal::ctrl_c;
+use tracing::{debug, info, instrument, trace, warn};
+
+use crate::command::serve::args::ServeArgs;
+use crate::html_site::{render_page, render_search};
+use crate::local_backup::{read_solutions, LocalSolution};
+use crate::search::{search, SearchOptions};
+use crate::Result;
+
+/// Command wrapper used for the [`Serve`](crate::command::Command::Serve) command.
+///
+/// The backup is read once when the command starts; pages are rendered when requested.
+#[derive(Debug)]
+pub struct ServeCommand {
+    args: ServeArgs,
+    solutions: Vec<LocalSolution>,
+}
+
+impl ServeCommand {
+    /// Creates a new [`ServeCommand`] using the provided [`args`](ServeArgs).
+    ///
+    /// The solutions in the backup are read (and decrypted, if needed) right away.
+    pub async fn new(args: ServeArgs) -> Result<Arc<Self>> {
+        let cipher = args
+            .encryption
+            .cipher()
+            .with_context(|| "failed to initialize decryption")?;
+        let solutions = read_solutions(&args.path, cipher.as_ref())
+            .await
+            .with_context(|| format!("failed to read backup {}", args.path.display()))?;
+
+        Ok(Arc::new(Self { args, solutions }))
+    }
+
+    /// Serve the backup until interrupted.
+    #[instrument(skip_all)]
+    pub async fn execute(this: Arc<Self>) -> Result<()> {
+        trace!(?this.args);
+
+        let address = SocketAddr::from((Ipv4Addr::LOCALHOST, this.args.port));
+        let make_service = make_service_fn(move |_| {
+            let this = this.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| Self::handle(this.clone(), request)))
+            }
+        });
+        let server = Server::try_bind(&address)
+            .with_context(|| format!("failed to listen on port {}", address.port()))?
+            .serve(make_service);
+
+        info!("Serving backup on http://{address}; press Ctrl-C to stop");
+        server
+            .with_graceful_shutdown(async {
+                ctrl_c().await.ok();
+            })
+            .await
+            .with_context(|| "failed to serve backup")
+    }
+
+    async fn handle(
+        this: Arc<Self>,
+        request: Request<Body>,
+    ) -> std::result::Result<Response<Body>, Infallible> {
+        debug!(method = %request.method(), uri = %request.uri(), "Handling request");
+
+        let response = match *request.method() {
+            Method::GET => this.respond(&request).unwrap_or_else(|error| {
+                warn!("Failed to handle request for {}: {error:#}", request.uri());
+                response(StatusCode::INTERNAL_SERVER_ERROR, "text/plain", format!("{error:#}"))
+            }),
+            _ => {
+                response(StatusCode::METHOD_NOT_ALLOWED, "text/plain", "Method not allowed".into())
+            },
+        };
+        Ok(response)
+    }
+
+    fn respond(&self, request: &Request<Body>) -> Result<Response<Body>> {
+        let path = percent_decode_str(request.uri().path().trim_start_matches('/'))
+            .decode_utf8()
+            .with_context(|| format!("invalid request path {}", request.uri().path()))?;
+        let path = match path.is_empty() || path.ends_with('/') {
+            true => format!("{path}index.html"),
+            false => path.into_owned(),
+        };
+
+        if path == "search.html" {
+            let query = query_param(request, "q").unwrap_or_default();
+            let matches = match query.trim() {
+                "" => Vec::new(),
+                query => {
+                    let regex = RegexBuilder::new(&regex::escape(query))
+                        .case_insensitive(true)
+                        .build()
+                        .with_context(|| format!("invalid search query {query}"))?;
+                    search(&self.solutions, &regex, SearchOptions::default())
+                },
+            };
+            return Ok(response(
+                StatusCode::OK,
+                "text/html; charset=utf-8",
+                render_search(&query, &matches),
+            ));
+        }
+
+        Ok(match render_page(&self.solutions, &path, true) {
+            Some(content) => {
+                let content_type = match path.ends_with(".css") {
+                    true => "text/css; charset=utf-8",
+                    false => "text/html; charset=utf-8",
+                };
+                response(StatusCode::OK, content_type, content)
+            },
+            None => response(StatusCode::NOT_FOUND, "text/plain", "Not found".into()),
+        })
+    }
+}
+
+fn query_param(request: &Request<Body>, name: &str) -> Option<String> {
+    let url = reqwest::Url::parse(&format!("http://localhost{}", request.uri())).ok()?;
+    let value = url
+        .query_pairs()
+        .find(|(param, _)| param == name)
+        .map(|(_, value)| value.into_owned());
+    value
+}
+
+fn response(status: StatusCode, content_type: &str, body: String) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, content_type)
+        .body(Body::from(body))
+        .expect("response should be valid")
+}
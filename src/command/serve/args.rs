@@ -0,0 +1,22 @@
+//! Arguments that can be passed to the [`Serve`](crate::command::Command::Serve) command.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::command::args::EncryptionArgs;
+
+/// Command-line arguments accepted by the [`Serve`](crate::command::Command::Serve) command.
+#[derive(Debug, Clone, Args)]
+pub struct ServeArgs {
+    /// Path of the backup to serve
+    pub path: PathBuf,
+
+    /// Local port to listen on
+    #[arg(short, long, default_value_t = 8080)]
+    pub port: u16,
+
+    /// Key used to decrypt the backed-up files, if the backup is encrypted
+    #[command(flatten)]
+    pub encryption: EncryptionArgs,
+}
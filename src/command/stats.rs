@@ -0,0 +1,109 @@
+//! Definition of the [`Stats`](crate::command::Command::Stats) command.
+
+pub mod args;
+mod report;
+
+use anyhow::Context;
+use mini_exercism::api;
+use tracing::{info, instrument, trace};
+
+use crate::cache::HttpCache;
+use crate::command::detail::get_all_solutions;
+use crate::command::stats::args::{StatsArgs, StatsFormat};
+use crate::command::stats::report::{to_csv, SolutionRecord, Stats};
+use crate::credentials::{resolve_credentials, validate_credentials};
+use crate::local_backup::read_solutions;
+use crate::local_server::LocalServer;
+use crate::Result;
+
+/// Command wrapper used for the [`Stats`](crate::command::Command::Stats) command.
+#[derive(Debug)]
+pub struct StatsCommand {
+    args: StatsArgs,
+}
+
+impl StatsCommand {
+    /// Creates a new [`StatsCommand`] using the provided [`args`](StatsArgs).
+    pub fn new(args: StatsArgs) -> Self {
+        Self { args }
+    }
+
+    /// Compute statistics and print them to standard output.
+    #[instrument(skip_all)]
+    pub async fn execute(&self) -> Result<()> {
+        trace!(?self.args);
+
+        let records = match &self.args.path {
+            Some(path) => {
+                info!("Computing statistics for backup {}", path.display());
+                self.records_from_backup(path).await?
+            },
+            None => {
+                info!("Computing statistics from the Exercism API");
+                self.records_from_api().await?
+            },
+        };
+
+        let output = match self.args.format {
+            StatsFormat::Table => Stats::compute(&records, self.args.top).to_table(),
+            StatsFormat::Json => {
+                let stats = Stats::compute(&records, self.args.top);
+                serde_json::to_string_pretty(&stats)
+                    .with_context(|| "failed to serialize statistics")?
+                    + "\n"
+            },
+            StatsFormat::Csv => to_csv(&records),
+        };
+        print!("{output}");
+
+        Ok(())
+    }
+
+    async fn records_from_backup(&self, path: &std::path::Path) -> Result<Vec<SolutionRecord>> {
+        let cipher = self
+            .args
+            .encryption
+            .cipher()
+            .with_context(|| "failed to initialize decryption")?;
+
+        let solutions = read_solutions(path, cipher.as_ref())
+            .await
+            .with_context(|| format!("failed to read backup {}", path.display()))?;
+        let missing_metadata = solutions
+            .iter()
+            .filter(|solution| solution.metadata.is_none())
+            .count();
+        if missing_metadata > 0 {
+            info!(
+                "{missing_metadata} solution(s) have no metadata (they were backed up by an older \
+                version of exsb); their status and iterations are unknown"
+            );
+        }
+
+        Ok(solutions.iter().map(SolutionRecord::from).collect())
+    }
+
+    async fn records_from_api(&self) -> Result<Vec<SolutionRecord>> {
+        let (credentials, source) = resolve_credentials(&self.args.credentials)?;
+        let upstream_client = self.args.http.build_http_client()?;
+        let cache = HttpCache::start(
+            &self.args.cache,
+            upstream_client.clone(),
+            self.args.api_base_url.as_deref(),
+        )
+        .await?;
+        let (http_client, api_base_url) = match &cache {
+            Some(cache) => (LocalServer::http_client()?, Some(cache.uri())),
+            None => (upstream_client, self.args.api_base_url.clone()),
+        };
+        let api_base_url = api_base_url.as_deref();
+        let v1_client = build_client!(api::v1::Client, http_client, credentials, api_base_url);
+        let v2_client = build_client!(api::v2::Client, http_client, credentials, api_base_url);
+
+        validate_credentials(&v1_client, &source).await?;
+
+        let solutions = get_all_solutions(&v2_client).await?;
+
+        Ok(solutions.iter().map(SolutionRecord::from).collect())
+    }
+}
@@ -0,0 +1,60 @@
+//! Arguments that can be passed to the [`Stats`](crate::command::Command::Stats) command.
+
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+
+use crate::command::args::{CacheArgs, CredentialsArgs, EncryptionArgs, HttpClientArgs};
+
+/// Command-line arguments accepted by the [`Stats`](crate::command::Command::Stats) command.
+#[derive(Debug, Clone, Args)]
+pub struct StatsArgs {
+    /// Path of the backup to compute statistics for
+    #[arg(required_unless_present = "from_api")]
+    pub path: Option<PathBuf>,
+
+    /// Compute statistics from the Exercism API instead of a local backup
+    #[arg(long, default_value_t = false, conflicts_with = "path")]
+    pub from_api: bool,
+
+    /// Exercism.org API token to use (only used with --from-api)
+    #[command(flatten)]
+    pub credentials: CredentialsArgs,
+
+    /// Configuration of the HTTP client used to access the Exercism API
+    #[command(flatten)]
+    pub http: HttpClientArgs,
+
+    /// Configuration of the cache of Exercism API responses
+    #[command(flatten)]
+    pub cache: CacheArgs,
+
+    /// Base URL of the Exercism API (only used for testing, see `exsb dev fake-server`)
+    #[arg(long, hide = true)]
+    pub api_base_url: Option<String>,
+
+    /// Key used to decrypt the backed-up files, if the backup is encrypted
+    #[command(flatten)]
+    pub encryption: EncryptionArgs,
+
+    /// Format used to output statistics
+    #[arg(long, value_enum, default_value_t = StatsFormat::Table)]
+    pub format: StatsFormat,
+
+    /// Number of largest solutions to list
+    #[arg(long, value_name = "N", default_value_t = 10)]
+    pub top: usize,
+}
+
+/// Possible output formats for statistics (see [`StatsArgs::format`]).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum StatsFormat {
+    /// Human-readable tables
+    Table,
+
+    /// JSON document containing all statistics
+    Json,
+
+    /// CSV file containing one record per solution, for further processing
+    Csv,
+}
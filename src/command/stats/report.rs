@@ -0,0 +1,365 @@
+//! Computation and rendering of the statistics output by the [`Stats`](crate::command::Command::Stats) command.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use mini_exercism::api::v2::solution::Solution;
+use serde::Serialize;
+
+use crate::command::backup::args::SolutionStatus;
+use crate::local_backup::LocalSolution;
+
+/// Information about a single solution, from a local backup or from the Exercism API.
+///
+/// Fields that cannot be determined from the source are [`None`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SolutionRecord {
+    pub track: String,
+    pub exercise: String,
+    pub status: Option<SolutionStatus>,
+    pub iterations: Option<i64>,
+    pub completed_at: Option<String>,
+    pub files: Option<usize>,
+    pub lines: Option<u64>,
+    pub bytes: Option<u64>,
+}
+
+impl From<&LocalSolution> for SolutionRecord {
+    fn from(solution: &LocalSolution) -> Self {
+        let metadata = solution.metadata.as_ref();
+        let lines = solution.files.iter().filter_map(|file| file.lines()).sum();
+
+        Self {
+            track: solution.track.clone(),
+            exercise: solution.exercise.clone(),
+            status: metadata.and_then(|metadata| metadata.status.try_into().ok()),
+            iterations: metadata.map(|metadata| i64::from(metadata.num_iterations)),
+            completed_at: metadata.and_then(|metadata| metadata.completed_at.clone()),
+            files: Some(solution.files.len()),
+            lines: Some(lines),
+            bytes: Some(
+                solution
+                    .files
+                    .iter()
+                    .map(|file| file.content.len() as u64)
+                    .sum(),
+            ),
+        }
+    }
+}
+
+impl From<&Solution> for SolutionRecord {
+    fn from(solution: &Solution) -> Self {
+        Self {
+            track: solution.track.name.clone(),
+            exercise: solution.exercise.name.clone(),
+            status: solution.status.try_into().ok(),
+            iterations: Some(i64::from(solution.num_iterations)),
+            completed_at: solution.completed_at.clone(),
+            files: None,
+            lines: solution
+                .num_loc
+                .and_then(|num_loc| u64::try_from(num_loc).ok()),
+            bytes: None,
+        }
+    }
+}
+
+/// Statistics for a single track (or for all tracks, see [`Stats::total`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TrackStats {
+    pub track: String,
+    pub solutions: usize,
+    pub submitted: usize,
+    pub completed: usize,
+    pub published: usize,
+    pub other: usize,
+    pub iterations: Option<i64>,
+    pub lines: Option<u64>,
+}
+
+impl TrackStats {
+    fn add(&mut self, record: &SolutionRecord) {
+        self.solutions += 1;
+        match record.status {
+            Some(SolutionStatus::Submitted) => self.submitted += 1,
+            Some(SolutionStatus::Completed) => self.completed += 1,
+            Some(SolutionStatus::Published) => self.published += 1,
+            None => self.other += 1,
+        }
+        if let Some(iterations) = record.iterations {
+            *self.iterations.get_or_insert(0) += iterations;
+        }
+        if let Some(lines) = record.lines {
+            *self.lines.get_or_insert(0) += lines;
+        }
+    }
+}
+
+/// Number of solutions completed in a given month.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MonthStats {
+    pub month: String,
+    pub completed: usize,
+}
+
+/// Statistics computed from a set of [`SolutionRecord`]s.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Stats {
+    pub total: TrackStats,
+    pub tracks: Vec<TrackStats>,
+    pub timeline: Vec<MonthStats>,
+    pub largest: Vec<SolutionRecord>,
+}
+
+impl Stats {
+    /// Computes statistics, keeping the `top` largest solutions.
+    pub fn compute(records: &[SolutionRecord], top: usize) -> Self {
+        let mut total = TrackStats { track: "total".into(), ..TrackStats::default() };
+        let mut tracks: BTreeMap<&str, TrackStats> = BTreeMap::new();
+        let mut timeline: BTreeMap<&str, usize> = BTreeMap::new();
+
+        for record in records {
+            total.add(record);
+            tracks
+                .entry(&record.track)
+                .or_insert_with(|| TrackStats {
+                    track: record.track.clone(),
+                    ..TrackStats::default()
+                })
+                .add(record);
+            if let Some(month) = record
+                .completed_at
+                .as_deref()
+                .and_then(|date| date.get(..7))
+            {
+                *timeline.entry(month).or_default() += 1;
+            }
+        }
+
+        let mut largest = records
+            .iter()
+            .filter(|record| record.lines.is_some() || record.bytes.is_some())
+            .cloned()
+            .collect::<Vec<_>>();
+        largest.sort_by_key(|record| Reverse((record.lines, record.bytes)));
+        largest.truncate(top);
+
+        Self {
+            total,
+            tracks: tracks.into_values().collect(),
+            timeline: timeline
+                .into_iter()
+                .map(|(month, completed)| MonthStats { month: month.into(), completed })
+                .collect(),
+            largest,
+        }
+    }
+
+    /// Renders these statistics as human-readable tables.
+    pub fn to_table(&self) -> String {
+        let mut output = String::new();
+
+        let track_row = |stats: &TrackStats| {
+            vec![
+                stats.track.clone(),
+                stats.solutions.to_string(),
+                stats.submitted.to_string(),
+                stats.completed.to_string(),
+                stats.published.to_string(),
+                stats.other.to_string(),
+                optional(stats.iterations),
+                optional(stats.lines),
+            ]
+        };
+        let mut rows = vec![[
+            "Track",
+            "Solutions",
+            "Submitted",
+            "Completed",
+            "Published",
+            "Other",
+            "Iterations",
+            "Lines",
+        ]
+        .map(String::from)
+        .to_vec()];
+        rows.extend(self.tracks.iter().map(track_row));
+        rows.push(track_row(&self.total));
+        write_table(&mut output, &rows);
+
+        if !self.timeline.is_empty() {
+            let mut rows = vec![vec!["Month".to_string(), "Completed".to_string()]];
+            rows.extend(
+                self.timeline
+                    .iter()
+                    .map(|month| vec![month.month.clone(), month.completed.to_string()]),
+            );
+            output.push('\n');
+            write_table(&mut output, &rows);
+        }
+
+        if !self.largest.is_empty() {
+            let mut rows = vec![["Largest solutions", "Lines", "Files", "Bytes"]
+                .map(String::from)
+                .to_vec()];
+            rows.extend(self.largest.iter().map(|record| {
+                vec![
+                    format!("{}/{}", record.track, record.exercise),
+                    optional(record.lines),
+                    optional(record.files),
+                    optional(record.bytes),
+                ]
+            }));
+            output.push('\n');
+            write_table(&mut output, &rows);
+        }
+
+        output
+    }
+}
+
+/// Renders solution records as CSV, with a header line.
+pub fn to_csv(records: &[SolutionRecord]) -> String {
+    let mut output =
+        String::from("track,exercise,status,iterations,completed_at,files,lines,bytes\n");
+
+    for record in records {
+        let status = record
+            .status
+            .and_then(|status| clap::ValueEnum::to_possible_value(&status))
+            .map(|value| value.get_name().to_string());
+        let fields = [
+            csv_field(&record.track),
+            csv_field(&record.exercise),
+            status.unwrap_or_default(),
+            optional(record.iterations),
+            csv_field(record.completed_at.as_deref().unwrap_or_default()),
+            optional(record.files),
+            optional(record.lines),
+            optional(record.bytes),
+        ];
+        output.push_str(&fields.join(","));
+        output.push('\n');
+    }
+
+    output
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+fn csv_field(value: &str) -> String {
+    match value.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_string(),
+    }
+}
+
+/// Writes a table with aligned columns; the first column is left-aligned, others right-aligned.
+fn write_table(output: &mut String, rows: &[Vec<String>]) {
+    let num_columns = rows.iter().map(Vec::len).max().unwrap_or_default();
+    let widths = (0..num_columns)
+        .map(|column| {
+            rows.iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    for row in rows {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(column, (cell, width))| match column {
+                0 => format!("{cell:<width$}"),
+                _ => format!("{cell:>width$}"),
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+        writeln!(output, "{}", line.trim_end()).expect("writing to a String should not fail");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(track: &str, exercise: &str, status: Option<SolutionStatus>) -> SolutionRecord {
+        SolutionRecord {
+            track: track.into(),
+            exercise: exercise.into(),
+            status,
+            iterations: Some(2),
+            completed_at: status
+                .filter(|status| *status >= SolutionStatus::Completed)
+                .map(|_| "2024-01-15T10:00:00Z".into()),
+            files: Some(1),
+            lines: Some(exercise.len() as u64),
+            bytes: None,
+        }
+    }
+
+    #[test]
+    fn test_compute() {
+        let records = vec![
+            record("rust", "poker", Some(SolutionStatus::Submitted)),
+            record("rust", "hello-world", Some(SolutionStatus::Published)),
+            record("elixir", "two-fer", Some(SolutionStatus::Completed)),
+            record("elixir", "lasagna", None),
+        ];
+
+        let stats = Stats::compute(&records, 2);
+
+        assert_eq!(4, stats.total.solutions);
+        assert_eq!(Some(8), stats.total.iterations);
+        assert_eq!(
+            vec!["elixir", "rust"],
+            stats
+                .tracks
+                .iter()
+                .map(|t| t.track.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!((1, 1, 0, 1), {
+            let elixir = &stats.tracks[0];
+            (elixir.solutions - elixir.other, elixir.completed, elixir.published, elixir.other)
+        });
+        assert_eq!(vec![MonthStats { month: "2024-01".into(), completed: 2 }], stats.timeline);
+        assert_eq!(
+            vec!["hello-world", "two-fer"],
+            stats
+                .largest
+                .iter()
+                .map(|r| r.exercise.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let mut rust = record("rust", "poker", Some(SolutionStatus::Submitted));
+        rust.completed_at = Some("a,\"b\"".into());
+
+        assert_eq!(
+            "track,exercise,status,iterations,completed_at,files,lines,bytes\n\
+            rust,poker,submitted,2,\"a,\"\"b\"\"\",1,5,\n",
+            to_csv(&[rust])
+        );
+    }
+
+    #[test]
+    fn test_to_table() {
+        let stats = Stats::compute(&[record("rust", "poker", Some(SolutionStatus::Completed))], 10);
+
+        let table = stats.to_table();
+        assert!(table.starts_with("Track  Solutions  Submitted"));
+        assert!(table.contains("\n2024-01          1\n"));
+        assert!(table.contains("\nrust/poker             5      1\n"));
+    }
+}
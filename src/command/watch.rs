@@ -0,0 +1,152 @@
+//! Definition of the [`Watch`](crate::command::Command::Watch) command.
+
+pub mod args;
+
+use std::time::Duration;
+
+use anyhow::anyhow;
+use tokio::signal::ctrl_c;
+use tokio::time::sleep;
+use tracing::{debug, info, instrument, trace, warn};
+
+use crate::command::backup::BackupCommand;
+use crate::command::watch::args::WatchArgs;
+use crate::http_session::HttpSession;
+use crate::Result;
+
+/// Command wrapper used for the [`Watch`](crate::command::Command::Watch) command.
+#[derive(Debug)]
+pub struct WatchCommand {
+    args: WatchArgs,
+}
+
+impl WatchCommand {
+    /// Creates a new [`WatchCommand`] using the provided [`args`](WatchArgs).
+    pub fn new(mut args: WatchArgs) -> Result<Self> {
+        if args.min_interval > args.max_interval {
+            return Err(anyhow!("--min-interval must not be greater than --max-interval"));
+        }
+
+        // Polls must see new iterations, so cached listings are always revalidated.
+        args.backup.cache.cache_ttl = Duration::ZERO;
+
+        Ok(Self { args })
+    }
+
+    /// Watch for changes and back up changed solutions, until interrupted.
+    #[instrument(skip_all)]
+    pub async fn execute(&self) -> Result<()> {
+        trace!(?self.args);
+
+        // The session must outlive all backups, since API clients talk to it.
+        let session = HttpSession::start(&self.args.backup).await?;
+        let backup_command =
+            BackupCommand::with_session(self.args.backup.clone(), session.as_ref())?;
+
+        info!(
+            "Watching for new iterations to back up to {}; press Ctrl-C to stop",
+            self.args.backup.path.display()
+        );
+        let mut interval = PollInterval::new(self.args.min_interval, self.args.max_interval);
+        let mut polls = 0;
+        let mut interrupted = false;
+        loop {
+            // A check interrupted midway could leave a solution half-written, so it is completed
+            // before stopping.
+            let check = BackupCommand::backup_changed_solutions(backup_command.clone());
+            tokio::pin!(check);
+            let result = tokio::select! {
+                result = &mut check => result,
+                _ = ctrl_c() => {
+                    info!("Finishing current check before stopping...");
+                    interrupted = true;
+                    check.await
+                },
+            };
+            polls += 1;
+
+            match result {
+                Ok(0) => {
+                    debug!("No changed solutions");
+                    interval.idle();
+                },
+                Ok(num_changed) => {
+                    info!("Backed up {num_changed} changed solution(s)");
+                    interval.active();
+                },
+                // If the first check fails, chances are the following ones will too (e.g. invalid token).
+                Err(error) if polls == 1 => return Err(error),
+                Err(error) => {
+                    warn!("Failed to back up changed solutions: {error:#}");
+                    interval.idle();
+                },
+            }
+
+            if interrupted
+                || self
+                    .args
+                    .max_polls
+                    .map_or(false, |max_polls| polls >= max_polls)
+            {
+                break;
+            }
+
+            debug!("Next check in {}", humantime::format_duration(interval.current()));
+            tokio::select! {
+                _ = sleep(interval.current()) => (),
+                _ = ctrl_c() => break,
+            }
+        }
+
+        info!("Stopped watching for new iterations");
+        Ok(())
+    }
+}
+
+/// Interval between checks for changes, adapting to activity: it is reset to its minimum when
+/// changes are found, and doubled (up to its maximum) when they aren't.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct PollInterval {
+    min: Duration,
+    max: Duration,
+    current: Duration,
+}
+
+impl PollInterval {
+    fn new(min: Duration, max: Duration) -> Self {
+        Self { min, max, current: min }
+    }
+
+    fn current(&self) -> Duration {
+        self.current
+    }
+
+    fn active(&mut self) {
+        self.current = self.min;
+    }
+
+    fn idle(&mut self) {
+        self.current = self.current.saturating_mul(2).clamp(self.min, self.max);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll_interval() {
+        let mut interval = PollInterval::new(Duration::from_secs(30), Duration::from_secs(100));
+        assert_eq!(Duration::from_secs(30), interval.current());
+
+        interval.idle();
+        assert_eq!(Duration::from_secs(60), interval.current());
+        interval.idle();
+        assert_eq!(Duration::from_secs(100), interval.current());
+        interval.idle();
+        assert_eq!(Duration::from_secs(100), interval.current());
+
+        interval.active();
+        assert_eq!(Duration::from_secs(30), interval.current());
+    }
+}
@@ -0,0 +1,27 @@
+//! Arguments that can be passed to the [`Watch`](crate::command::Command::Watch) command.
+
+use std::time::Duration;
+
+use clap::Args;
+
+use crate::command::backup::args::BackupArgs;
+
+/// Command-line arguments accepted by the [`Watch`](crate::command::Command::Watch) command.
+#[derive(Debug, Clone, Args)]
+pub struct WatchArgs {
+    /// Arguments used to back up changed solutions
+    #[command(flatten)]
+    pub backup: BackupArgs,
+
+    /// Time to wait between checks for changes while solutions are being submitted (e.g. `30s`)
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration, default_value = "30s")]
+    pub min_interval: Duration,
+
+    /// Maximum time to wait between checks for changes when no solutions are being submitted
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration, default_value = "10m")]
+    pub max_interval: Duration,
+
+    /// Stop after this number of checks for changes (only used for testing)
+    #[arg(long, hide = true)]
+    pub max_polls: Option<u64>,
+}
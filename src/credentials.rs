@@ -0,0 +1,263 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use mini_exercism::api;
+use mini_exercism::cli::get_cli_credentials;
+use mini_exercism::core::Credentials;
+use tracing::trace;
+
+use crate::command::args::CredentialsArgs;
+use crate::Result;
+
+/// Name of the environment variable that can be used to specify the Exercism API token.
+pub const TOKEN_ENV_VAR: &str = "EXERCISM_TOKEN";
+
+/// Where the [`Credentials`] used to access the Exercism API come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CredentialSource {
+    Flag,
+    TokenFile(PathBuf),
+    Environment,
+    #[cfg(feature = "keyring")]
+    Keyring,
+    CliConfig,
+    AccountsFile {
+        path: PathBuf,
+        account: String,
+    },
+    #[cfg(feature = "keyring")]
+    Terminal,
+    Replay(PathBuf),
+}
+
+impl Display for CredentialSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CredentialSource::Flag => write!(f, "--token option"),
+            CredentialSource::TokenFile(path) => write!(f, "token file {}", path.display()),
+            CredentialSource::Environment => write!(f, "{TOKEN_ENV_VAR} environment variable"),
+            #[cfg(feature = "keyring")]
+            CredentialSource::Keyring => write!(f, "system keyring"),
+            CredentialSource::CliConfig => write!(f, "Exercism CLI configuration"),
+            CredentialSource::AccountsFile { path, account } => {
+                write!(f, "accounts file {} (account {account})", path.display())
+            },
+            #[cfg(feature = "keyring")]
+            CredentialSource::Terminal => write!(f, "terminal input"),
+            CredentialSource::Replay(path) => write!(f, "replayed session {}", path.display()),
+        }
+    }
+}
+
+impl CredentialSource {
+    /// Returns a suggestion of what the user can do to fix a rejected token from this source.
+    pub fn remedy(&self) -> String {
+        let new_token = "a valid token can be found at https://exercism.org/settings/api_cli";
+        match self {
+            CredentialSource::Flag => format!("Make sure {new_token}"),
+            #[cfg(feature = "keyring")]
+            CredentialSource::Terminal => format!("Make sure {new_token}"),
+            CredentialSource::TokenFile(path) => {
+                format!("Update the token in {} ({new_token})", path.display())
+            },
+            CredentialSource::Environment => {
+                format!("Update the {TOKEN_ENV_VAR} environment variable ({new_token})")
+            },
+            #[cfg(feature = "keyring")]
+            CredentialSource::Keyring => {
+                format!("Run `exsb auth login` to store a new token ({new_token})")
+            },
+            CredentialSource::CliConfig => format!(
+                "Run `exercism configure --token=<token>` to update the Exercism CLI token ({new_token})"
+            ),
+            CredentialSource::AccountsFile { path, account } => format!(
+                "Update the token of account {account} in {} ({new_token})",
+                path.display()
+            ),
+            CredentialSource::Replay(path) => format!(
+                "The token was rejected when the session in {} was recorded; record a new session \
+                with --record ({new_token})",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// Determines which [`Credentials`] to use, given command-line arguments.
+///
+/// Sources are tried in this order:
+///
+/// 1. `--token` option
+/// 2. `--token-file` option
+/// 3. [`TOKEN_ENV_VAR`] environment variable
+/// 4. Token stored in the system keyring (via `exsb auth login`)
+/// 5. Exercism CLI configuration
+pub fn resolve_credentials(args: &CredentialsArgs) -> Result<(Credentials, CredentialSource)> {
+    let (credentials, source) = match explicit_credentials(args)? {
+        Some(credentials) => credentials,
+        None => match keyring_credentials() {
+            Some(credentials) => credentials,
+            None => {
+                let credentials = get_cli_credentials().with_context(|| {
+                    format!(
+                        "failed to get Exercism CLI credentials (API token can also be specified \
+                        via --token, --token-file or the {TOKEN_ENV_VAR} environment variable)"
+                    )
+                })?;
+                (credentials, CredentialSource::CliConfig)
+            },
+        },
+    };
+
+    trace!(%source, "Resolved Exercism credentials");
+    Ok((credentials, source))
+}
+
+/// Returns [`Credentials`] explicitly specified by the user, if any.
+///
+/// This only looks at the `--token` and `--token-file` options and at the [`TOKEN_ENV_VAR`]
+/// environment variable, in that order.
+pub fn explicit_credentials(
+    args: &CredentialsArgs,
+) -> Result<Option<(Credentials, CredentialSource)>> {
+    Ok(if let Some(token) = &args.token {
+        Some((Credentials::from_api_token(token), CredentialSource::Flag))
+    } else if let Some(token_file) = &args.token_file {
+        Some((read_token_file(token_file)?, CredentialSource::TokenFile(token_file.clone())))
+    } else {
+        token_from_env()
+            .map(|token| (Credentials::from_api_token(token), CredentialSource::Environment))
+    })
+}
+
+/// Makes sure the credentials used by the given client are accepted by the Exercism API.
+///
+/// If the token is rejected, the returned error names the [`source`](CredentialSource) of the
+/// token and suggests how to fix the problem.
+pub async fn validate_credentials(
+    v1_client: &api::v1::Client,
+    source: &CredentialSource,
+) -> Result<()> {
+    match v1_client.validate_token().await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(anyhow!(
+            "Exercism API token from {source} was rejected: it is invalid, expired or has been \
+            revoked. {}",
+            source.remedy()
+        )),
+        Err(error) => Err(error).with_context(|| {
+            format!("failed to validate Exercism API token from {source} with the Exercism API")
+        }),
+    }
+}
+
+/// Reads an Exercism API token from a file.
+///
+/// Leading and trailing whitespace (including the final newline) is ignored.
+pub fn read_token_file(path: &Path) -> Result<Credentials> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read token file {}", path.display()))?;
+
+    match content.trim() {
+        "" => Err(anyhow!("token file {} is empty", path.display())),
+        token => Ok(Credentials::from_api_token(token)),
+    }
+}
+
+fn token_from_env() -> Option<String> {
+    std::env::var(TOKEN_ENV_VAR)
+        .ok()
+        .filter(|token| !token.trim().is_empty())
+}
+
+#[cfg(feature = "keyring")]
+fn keyring_credentials() -> Option<(Credentials, CredentialSource)> {
+    // Not all systems have a keyring available, so failure to access it is not fatal.
+    match keyring_store::load_token() {
+        Ok(token) => {
+            token.map(|token| (Credentials::from_api_token(token), CredentialSource::Keyring))
+        },
+        Err(error) => {
+            tracing::debug!("Failed to look up token in system keyring: {error:#}");
+            None
+        },
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+fn keyring_credentials() -> Option<(Credentials, CredentialSource)> {
+    None
+}
+
+/// Storage of the Exercism API token in the system keyring.
+#[cfg(feature = "keyring")]
+pub mod keyring_store {
+    use ::keyring::Entry;
+    use anyhow::Context;
+
+    use crate::Result;
+
+    const SERVICE: &str = "exsb";
+    const USER: &str = "exercism-api-token";
+
+    fn entry() -> Result<Entry> {
+        Entry::new(SERVICE, USER).with_context(|| "failed to access system keyring")
+    }
+
+    pub fn load_token() -> Result<Option<String>> {
+        match entry()?.get_password() {
+            Ok(token) => Ok(Some(token)),
+            Err(::keyring::Error::NoEntry) => Ok(None),
+            Err(error) => Err(error).with_context(|| "failed to read token from system keyring"),
+        }
+    }
+
+    pub fn store_token(token: &str) -> Result<()> {
+        entry()?
+            .set_password(token)
+            .with_context(|| "failed to store token in system keyring")
+    }
+
+    /// Deletes the token from the keyring, returning `false` if there was none.
+    pub fn delete_token() -> Result<bool> {
+        match entry()?.delete_password() {
+            Ok(()) => Ok(true),
+            Err(::keyring::Error::NoEntry) => Ok(false),
+            Err(error) => Err(error).with_context(|| "failed to delete token from system keyring"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flag_has_priority() {
+        let args = CredentialsArgs {
+            token: Some("from-flag".into()),
+            token_file: Some("does-not-exist".into()),
+        };
+
+        let (credentials, source) = resolve_credentials(&args).unwrap();
+        assert_eq!("from-flag", credentials.api_token());
+        assert_eq!(CredentialSource::Flag, source);
+    }
+
+    #[test]
+    fn test_missing_token_file() {
+        let args = CredentialsArgs { token: None, token_file: Some("does-not-exist".into()) };
+
+        assert!(resolve_credentials(&args).is_err());
+    }
+
+    #[test]
+    fn test_accounts_file_source() {
+        let source =
+            CredentialSource::AccountsFile { path: "accounts.txt".into(), account: "bot".into() };
+
+        assert_eq!("accounts file accounts.txt (account bot)", source.to_string());
+        assert!(source.remedy().contains("account bot in accounts.txt"));
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Context};
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use sha2::{Digest, Sha256};
+
+use crate::Result;
+
+/// Header written at the beginning of every encrypted file.
+pub const MAGIC: &[u8; 8] = b"exsbenc1";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + SALT_LEN + NONCE_LEN;
+
+/// Secret used to derive encryption keys.
+#[derive(Clone)]
+pub enum Secret {
+    KeyFile { path: PathBuf, contents: Vec<u8> },
+    Passphrase(String),
+}
+
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print secret material, even in trace logs.
+        match self {
+            Secret::KeyFile { path, .. } => f.debug_tuple("KeyFile").field(path).finish(),
+            Secret::Passphrase(_) => f.write_str("Passphrase(<redacted>)"),
+        }
+    }
+}
+
+impl Secret {
+    pub fn from_key_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read(path)
+            .with_context(|| format!("failed to read encryption key file {}", path.display()))?;
+        if contents.is_empty() {
+            return Err(anyhow!("encryption key file {} is empty", path.display()));
+        }
+
+        Ok(Self::KeyFile { path: path.to_path_buf(), contents })
+    }
+
+    fn derive_key(&self, salt: &[u8]) -> Result<Key> {
+        let mut key = Key::default();
+        match self {
+            Secret::KeyFile { contents, .. } => {
+                let mut hasher = Sha256::new();
+                hasher.update(salt);
+                hasher.update(contents);
+                key.copy_from_slice(&hasher.finalize());
+            },
+            Secret::Passphrase(passphrase) => {
+                Argon2::default()
+                    .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+                    .map_err(|err| anyhow!("failed to derive key from passphrase: {err}"))?;
+            },
+        }
+        Ok(key)
+    }
+}
+
+/// Authenticated cipher used to encrypt and decrypt backed-up files.
+///
+/// Each encrypted file is self-contained: it starts with [`MAGIC`], followed by the salt used to
+/// derive the key and the nonce, then the ciphertext (including the authentication tag).
+/// A single salt is used for all files encrypted by a given [`Cipher`] so that the (potentially
+/// expensive) key derivation is only performed once per run.
+pub struct Cipher {
+    secret: Secret,
+    salt: [u8; SALT_LEN],
+    cipher: ChaCha20Poly1305,
+    decryption_keys: Mutex<HashMap<[u8; SALT_LEN], ChaCha20Poly1305>>,
+}
+
+impl std::fmt::Debug for Cipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cipher")
+            .field("secret", &self.secret)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Cipher {
+    pub fn new(secret: Secret) -> Result<Self> {
+        let mut salt = [0; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let cipher = ChaCha20Poly1305::new(&secret.derive_key(&salt)?);
+
+        Ok(Self { secret, salt, cipher, decryption_keys: Mutex::new(HashMap::new()) })
+    }
+
+    pub fn is_encrypted(data: &[u8]) -> bool {
+        data.starts_with(MAGIC)
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| anyhow!("failed to encrypt data"))?;
+
+        let mut data = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+        data.extend_from_slice(MAGIC);
+        data.extend_from_slice(&self.salt);
+        data.extend_from_slice(&nonce);
+        data.extend_from_slice(&ciphertext);
+        Ok(data)
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if !Self::is_encrypted(data) || data.len() < HEADER_LEN {
+            return Err(anyhow!("data is not in exsb encrypted format"));
+        }
+
+        let salt: [u8; SALT_LEN] = data[MAGIC.len()..MAGIC.len() + SALT_LEN]
+            .try_into()
+            .expect("salt slice should have the right length");
+        let nonce = Nonce::from_slice(&data[MAGIC.len() + SALT_LEN..HEADER_LEN]);
+        let ciphertext = &data[HEADER_LEN..];
+
+        let plaintext = if salt == self.salt {
+            self.cipher.decrypt(nonce, ciphertext)
+        } else {
+            let mut keys = self.decryption_keys.lock().unwrap();
+            let cipher = match keys.entry(salt) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    entry.insert(ChaCha20Poly1305::new(&self.secret.derive_key(&salt)?))
+                },
+            };
+            cipher.decrypt(nonce, ciphertext)
+        };
+
+        plaintext.map_err(|_| {
+            anyhow!("failed to decrypt data: wrong key, or data has been tampered with")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passphrase_cipher(passphrase: &str) -> Cipher {
+        Cipher::new(Secret::Passphrase(passphrase.into())).unwrap()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let cipher = passphrase_cipher("hunter2");
+        let encrypted = cipher.encrypt(b"fn main() {}").unwrap();
+
+        assert!(Cipher::is_encrypted(&encrypted));
+        assert_eq!(b"fn main() {}".to_vec(), cipher.decrypt(&encrypted).unwrap());
+    }
+
+    #[test]
+    fn test_decrypt_with_other_salt() {
+        let encrypted = passphrase_cipher("hunter2").encrypt(b"secret").unwrap();
+
+        let other_cipher = passphrase_cipher("hunter2");
+        assert_eq!(b"secret".to_vec(), other_cipher.decrypt(&encrypted).unwrap());
+    }
+
+    #[test]
+    fn test_wrong_key() {
+        let encrypted = passphrase_cipher("hunter2").encrypt(b"secret").unwrap();
+
+        assert!(passphrase_cipher("hunter3").decrypt(&encrypted).is_err());
+    }
+
+    #[test]
+    fn test_tampered_data() {
+        let cipher =
+            Cipher::new(Secret::KeyFile { path: "key".into(), contents: vec![42; 32] }).unwrap();
+        let mut encrypted = cipher.encrypt(b"secret").unwrap();
+        *encrypted.last_mut().unwrap() ^= 1;
+
+        assert!(cipher.decrypt(&encrypted).is_err());
+    }
+
+    #[test]
+    fn test_not_encrypted() {
+        assert!(passphrase_cipher("hunter2").decrypt(b"plain text").is_err());
+    }
+}
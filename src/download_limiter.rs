@@ -1,6 +1,8 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use tokio::sync::{Semaphore, SemaphorePermit};
+use tokio::time::{sleep, Instant};
 
 #[derive(Debug, Clone)]
 pub struct DownloadLimiter(Arc<Semaphore>);
@@ -24,3 +26,75 @@ impl DownloadLimiter {
         DownloadPermit { _permit: self.0.acquire().await.unwrap() }
     }
 }
+
+/// Limits the aggregate throughput of downloads, in bytes per second.
+///
+/// Uses a token bucket allowing bursts of up to one second worth of data. Downloads that exceed
+/// the rate go into debt and wait until it is paid back, so concurrent downloads share the rate.
+#[derive(Debug, Clone)]
+pub struct RateLimiter(Arc<Mutex<TokenBucket>>);
+
+impl RateLimiter {
+    pub fn new(bytes_per_second: u64) -> Self {
+        Self(Arc::new(Mutex::new(TokenBucket::new(bytes_per_second, Instant::now()))))
+    }
+
+    /// Accounts for `bytes` downloaded, waiting if needed to stay under the rate.
+    pub async fn consume(&self, bytes: usize) {
+        let wait = self
+            .0
+            .lock()
+            .expect("rate limiter lock should not be poisoned")
+            .reserve(bytes, Instant::now());
+        if !wait.is_zero() {
+            sleep(wait).await;
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    available: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(bytes_per_second: u64, now: Instant) -> Self {
+        let rate = bytes_per_second.max(1) as f64;
+        Self { rate, available: rate, last_refill: now }
+    }
+
+    /// Takes `bytes` from the bucket and returns how long to wait before using them.
+    fn reserve(&mut self, bytes: usize, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.available = (self.available + elapsed * self.rate).min(self.rate);
+        self.last_refill = now;
+
+        self.available -= bytes as f64;
+        match self.available < 0.0 {
+            true => Duration::from_secs_f64(-self.available / self.rate),
+            false => Duration::ZERO,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(1000, start);
+
+        assert_eq!(Duration::ZERO, bucket.reserve(1000, start));
+        assert_eq!(Duration::from_millis(500), bucket.reserve(500, start));
+        assert_eq!(Duration::from_millis(1000), bucket.reserve(500, start));
+
+        // Debt is paid back over time, and unused capacity doesn't accumulate past one second.
+        assert_eq!(Duration::ZERO, bucket.reserve(0, start + Duration::from_secs(1)));
+        assert_eq!(Duration::ZERO, bucket.reserve(1000, start + Duration::from_secs(10)));
+        assert_eq!(Duration::from_millis(250), bucket.reserve(250, start + Duration::from_secs(10)));
+    }
+}
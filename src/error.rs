@@ -14,6 +14,11 @@ pub type Error = anyhow::Error;
 /// Currently mapped to [`anyhow::Result`] in order to use our [`Error`] type.
 pub type Result<T> = anyhow::Result<T>;
 
+pub(crate) mod report;
+
+/// Multiple errors encountered while performing concurrent tasks.
+///
+/// When displayed, errors are grouped in an [`ErrorReport`](report::ErrorReport).
 #[derive(Debug)]
 pub(crate) struct MultiError(Vec<Error>);
 
@@ -31,14 +36,64 @@ impl MultiError {
     }
 }
 
+impl MultiError {
+    /// Returns the errors that were encountered.
+    pub fn errors(&self) -> &[Error] {
+        &self.0
+    }
+}
+
 impl Display for MultiError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Multiple errors encountered:\n")?;
-        self.0
-            .iter()
-            .enumerate()
-            .try_fold((), |_, (i, error)| writeln!(f, "{i}: {error}\n"))
+        write!(f, "{}", report::ErrorReport::from_errors(&self.0))
     }
 }
 
 impl std::error::Error for MultiError {}
+
+/// Part of a backup in which an error occurred (see [`ScopedError`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ErrorScope {
+    Account(String),
+    Solution { track: String, exercise: String },
+    File(String),
+}
+
+impl ErrorScope {
+    /// Wraps `error` in a [`ScopedError`] for this scope.
+    pub fn wrap(self, error: Error) -> Error {
+        ScopedError { scope: self, source: error }.into()
+    }
+}
+
+impl Display for ErrorScope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Account(name) => write!(f, "account {name}"),
+            Self::Solution { track, exercise } => write!(f, "solution to {track}/{exercise}"),
+            Self::File(path) => write!(f, "file {path}"),
+        }
+    }
+}
+
+/// Error that occurred while backing up part of an account.
+///
+/// Scopes are used to group errors by account, track, exercise and file when reporting them.
+#[derive(Debug)]
+pub(crate) struct ScopedError {
+    pub scope: ErrorScope,
+    pub source: Error,
+}
+
+impl Display for ScopedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to back up {}", self.scope)
+    }
+}
+
+impl std::error::Error for ScopedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
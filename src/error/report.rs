@@ -0,0 +1,366 @@
+//! Hierarchical reports of errors encountered while performing concurrent tasks.
+//!
+//! Errors wrapped in [`ScopedError`]s are grouped in a tree (account → track → exercise → file),
+//! and errors with identical root causes are counted together, so that a network outage results
+//! in something like "37 files failed: connection reset" instead of a long list of errors.
+
+use std::error::Error as StdError;
+use std::fmt::{Display, Formatter};
+
+use serde::Serialize;
+
+use crate::error::{Error, ErrorScope, MultiError, ScopedError};
+
+/// Number of names listed when multiple leaves share the same root cause.
+const MAX_LISTED_NAMES: usize = 3;
+
+/// Kind of [`ErrorNode`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    Account,
+    Track,
+    Exercise,
+    File,
+}
+
+impl NodeKind {
+    fn failed(self, count: usize) -> String {
+        let noun = match self {
+            Self::Account => "account",
+            Self::Track => "track",
+            Self::Exercise => "solution",
+            Self::File => "file",
+        };
+        match count {
+            1 => format!("1 {noun} failed"),
+            _ => format!("{count} {noun}s failed"),
+        }
+    }
+}
+
+/// Single error found in an [`ErrorReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReportedError {
+    /// Full error message, starting from the innermost scope.
+    pub message: String,
+
+    /// Message of the error's root cause.
+    pub cause: String,
+}
+
+impl ReportedError {
+    fn new(error: &(dyn StdError + 'static)) -> Self {
+        let chain = std::iter::successors(Some(error), |&error| error.source())
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        Self { message: chain.join(": "), cause: chain.last().cloned().unwrap_or_default() }
+    }
+}
+
+/// Part of an account (or the account itself) in which errors occurred.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ErrorNode {
+    pub kind: NodeKind,
+    pub name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ReportedError>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<ErrorNode>,
+}
+
+impl ErrorNode {
+    fn is_leaf(&self) -> bool {
+        self.children.is_empty() && self.errors.len() == 1
+    }
+}
+
+/// Number of parts of a given kind that failed with the same root cause.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FailureSummary {
+    /// Kind of parts that failed, or [`None`] for errors that are not tied to a part.
+    pub kind: Option<NodeKind>,
+    pub cause: String,
+    pub count: usize,
+}
+
+/// Report of errors grouped by account, track, exercise and file.
+///
+/// Displaying the report renders it as an indented tree, preceded by a summary of root causes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ErrorReport {
+    pub summary: Vec<FailureSummary>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ReportedError>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<ErrorNode>,
+}
+
+impl ErrorReport {
+    /// Creates a report for a single error, which can contain multiple errors (see [`MultiError`]).
+    pub fn from_error(error: &Error) -> Self {
+        Self::from_errors(std::slice::from_ref(error))
+    }
+
+    /// Creates a report for the given errors.
+    pub fn from_errors(errors: &[Error]) -> Self {
+        let mut report = Self::default();
+        for error in errors {
+            add_error(&mut report.errors, &mut report.children, error.as_ref());
+        }
+
+        add_to_summary(&mut report.summary, None, &report.errors);
+        for node in &report.children {
+            summarize(&mut report.summary, node);
+        }
+        report
+            .summary
+            .sort_by_key(|summary| std::cmp::Reverse(summary.count));
+
+        report
+    }
+}
+
+impl Display for ErrorReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for summary in &self.summary {
+            let failed = match (summary.kind, summary.count) {
+                (Some(kind), count) => kind.failed(count),
+                (None, 1) => "1 error".to_string(),
+                (None, count) => format!("{count} errors"),
+            };
+            writeln!(f, "{failed}: {}", summary.cause)?;
+        }
+
+        if !self.errors.is_empty() || !self.children.is_empty() {
+            writeln!(f)?;
+        }
+        for error in &self.errors {
+            writeln!(f, "{}", error.message)?;
+        }
+        write_nodes(f, &self.children, 0)
+    }
+}
+
+fn add_error(
+    errors: &mut Vec<ReportedError>,
+    children: &mut Vec<ErrorNode>,
+    error: &(dyn StdError + 'static),
+) {
+    let mut link = Some(error);
+    while let Some(current) = link {
+        if let Some(multi_error) = current.downcast_ref::<MultiError>() {
+            for error in multi_error.errors() {
+                add_error(errors, children, error.as_ref());
+            }
+            return;
+        }
+        if let Some(scoped_error) = current.downcast_ref::<ScopedError>() {
+            let node = scope_node(children, &scoped_error.scope);
+            add_error(&mut node.errors, &mut node.children, scoped_error.source.as_ref());
+            return;
+        }
+        link = current.source();
+    }
+
+    errors.push(ReportedError::new(error));
+}
+
+fn scope_node<'a>(children: &'a mut Vec<ErrorNode>, scope: &ErrorScope) -> &'a mut ErrorNode {
+    match scope {
+        ErrorScope::Account(name) => child_node(children, NodeKind::Account, name),
+        ErrorScope::Solution { track, exercise } => {
+            let track = child_node(children, NodeKind::Track, track);
+            child_node(&mut track.children, NodeKind::Exercise, exercise)
+        },
+        ErrorScope::File(path) => child_node(children, NodeKind::File, path),
+    }
+}
+
+fn child_node<'a>(
+    children: &'a mut Vec<ErrorNode>,
+    kind: NodeKind,
+    name: &str,
+) -> &'a mut ErrorNode {
+    let index = match children
+        .iter()
+        .position(|child| child.kind == kind && child.name == name)
+    {
+        Some(index) => index,
+        None => {
+            children.push(ErrorNode {
+                kind,
+                name: name.into(),
+                errors: Vec::new(),
+                children: Vec::new(),
+            });
+            children.len() - 1
+        },
+    };
+
+    &mut children[index]
+}
+
+fn summarize(summary: &mut Vec<FailureSummary>, node: &ErrorNode) {
+    add_to_summary(summary, Some(node.kind), &node.errors);
+    for child in &node.children {
+        summarize(summary, child);
+    }
+}
+
+fn add_to_summary(
+    summary: &mut Vec<FailureSummary>,
+    kind: Option<NodeKind>,
+    errors: &[ReportedError],
+) {
+    for error in errors {
+        match summary
+            .iter_mut()
+            .find(|summary| summary.kind == kind && summary.cause == error.cause)
+        {
+            Some(summary) => summary.count += 1,
+            None => summary.push(FailureSummary { kind, cause: error.cause.clone(), count: 1 }),
+        }
+    }
+}
+
+fn write_nodes(f: &mut Formatter<'_>, nodes: &[ErrorNode], depth: usize) -> std::fmt::Result {
+    let indent = "  ".repeat(depth);
+
+    // Leaves failing with the same root cause are grouped on a single line.
+    let mut groups: Vec<(NodeKind, &str, Vec<&str>)> = Vec::new();
+    for node in nodes.iter().filter(|node| node.is_leaf()) {
+        let cause = node.errors[0].cause.as_str();
+        match groups
+            .iter_mut()
+            .find(|(kind, group_cause, _)| *kind == node.kind && *group_cause == cause)
+        {
+            Some((_, _, names)) => names.push(&node.name),
+            None => groups.push((node.kind, cause, vec![&node.name])),
+        }
+    }
+    for (kind, cause, names) in &groups {
+        match names.as_slice() {
+            [name] => writeln!(f, "{indent}{name}: {cause}")?,
+            names => {
+                let mut listed = names[..names.len().min(MAX_LISTED_NAMES)].join(", ");
+                if names.len() > MAX_LISTED_NAMES {
+                    listed.push_str(&format!(" and {} more", names.len() - MAX_LISTED_NAMES));
+                }
+                writeln!(f, "{indent}{}: {cause} ({listed})", kind.failed(names.len()))?;
+            },
+        }
+    }
+
+    for node in nodes.iter().filter(|node| !node.is_leaf()) {
+        writeln!(f, "{indent}{}", node.name)?;
+        for error in &node.errors {
+            writeln!(f, "{indent}  {}", error.cause)?;
+        }
+        write_nodes(f, &node.children, depth + 1)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+
+    fn file_error(track: &str, exercise: &str, file: &str, cause: &str) -> Error {
+        let error = ErrorScope::File(file.into())
+            .wrap(anyhow!(cause.to_string()).context(format!("failed to download file {file}")));
+        let error = MultiError::check(vec![error], || "errors detected while backing up solution")
+            .unwrap_err();
+        ErrorScope::Solution { track: track.into(), exercise: exercise.into() }.wrap(error)
+    }
+
+    fn report() -> ErrorReport {
+        let errors = vec![
+            file_error("rust", "poker", "src/lib.rs", "connection reset"),
+            file_error("rust", "poker", "Cargo.toml", "connection reset"),
+            file_error("rust", "clock", "src/lib.rs", "connection reset"),
+            file_error("elixir", "two-fer", "lib/two_fer.ex", "not found"),
+            anyhow!("failed to fetch solutions for page 2"),
+        ];
+        let error =
+            MultiError::check(errors, || "errors detected while backing up solutions").unwrap_err();
+        let error =
+            MultiError::check(vec![ErrorScope::Account("alice".into()).wrap(error)], || {
+                "errors detected while backing up accounts"
+            })
+            .unwrap_err();
+
+        ErrorReport::from_error(&error)
+    }
+
+    #[test]
+    fn test_from_error() {
+        let report = report();
+
+        assert_eq!(
+            vec![
+                FailureSummary {
+                    kind: Some(NodeKind::File),
+                    cause: "connection reset".into(),
+                    count: 3
+                },
+                FailureSummary {
+                    kind: Some(NodeKind::Account),
+                    cause: "failed to fetch solutions for page 2".into(),
+                    count: 1
+                },
+                FailureSummary { kind: Some(NodeKind::File), cause: "not found".into(), count: 1 },
+            ],
+            report.summary
+        );
+
+        let alice = &report.children[0];
+        assert_eq!((NodeKind::Account, "alice"), (alice.kind, alice.name.as_str()));
+        assert_eq!(
+            vec!["rust", "elixir"],
+            alice
+                .children
+                .iter()
+                .map(|track| track.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "failed to download file Cargo.toml: connection reset",
+            alice.children[0].children[0].children[1].errors[0].message
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            "3 files failed: connection reset\n\
+            1 account failed: failed to fetch solutions for page 2\n\
+            1 file failed: not found\n\
+            \n\
+            alice\n  \
+              failed to fetch solutions for page 2\n  \
+              rust\n    \
+                poker\n      \
+                  2 files failed: connection reset (src/lib.rs, Cargo.toml)\n    \
+                clock\n      \
+                  src/lib.rs: connection reset\n  \
+              elixir\n    \
+                two-fer\n      \
+                  lib/two_fer.ex: not found\n",
+            report().to_string()
+        );
+    }
+
+    #[test]
+    fn test_serialize() {
+        let json = serde_json::to_value(report()).unwrap();
+
+        assert_eq!("file", json["summary"][0]["kind"]);
+        assert_eq!("exercise", json["children"][0]["children"][0]["children"][0]["kind"]);
+        assert!(json.get("errors").is_none());
+    }
+}
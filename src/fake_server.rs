@@ -0,0 +1,532 @@
+//! Fake Exercism API server, used for testing and demos.
+//!
+//! The [`FakeServer`] serves the subset of the Exercism API used by `exsb`: the v1 endpoints used
+//! to validate tokens, get a solution's files and download files, as well as the v2 endpoints used
+//! to list solutions (with paging) and a track's exercises. Its content is driven by a fixtures
+//! directory of fake accounts:
+//!
+//! ```text
+//! fixtures/
+//! └── <account>/
+//!     ├── token                           API token used to authenticate as this account
+//!     └── solutions/
+//!         └── <track>/
+//!             └── <exercise>/
+//!                 ├── solution.json       Optional fields of the v2 solution (status, etc.)
+//!                 └── files/              Files of the solution's latest iteration
+//! ```
+//!
+//! Any field of the solution that is not specified in `solution.json` is given a default value.
+//! The type of the solution's exercise can be specified with an additional `exercise_type` field
+//! (defaults to `practice`); only exercises with a solution are listed in their track.
+//! Like the real API, responses listing solutions or files include an `ETag` header, and requests
+//! with a matching `If-None-Match` header get a `304 Not Modified` response.
+//!
+//! To start a fake server from the command line, use the hidden `exsb dev fake-server` command.
+//! To use it with other commands, pass the server's [`uri`](FakeServer::uri) to `--api-base-url`.
+
+use std::cmp::Reverse;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use anyhow::{anyhow, Context};
+use hyper::header::{HeaderMap, AUTHORIZATION, CONTENT_TYPE, ETAG, HOST, IF_NONE_MATCH};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use percent_encoding::percent_decode_str;
+use reqwest::Url;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use tokio::sync::oneshot;
+
+use crate::local_server::LocalServer;
+use crate::Result;
+
+/// Default number of solutions returned per page by the v2 solutions endpoint.
+pub const DEFAULT_PER_PAGE: usize = 25;
+
+/// Fake Exercism API server.
+///
+/// The server runs on its own thread, so that tests can block on commands using it without starving
+/// it. It is stopped when dropped.
+#[derive(Debug)]
+pub struct FakeServer {
+    uri: String,
+    received_requests: Arc<Mutex<Vec<ReceivedRequest>>>,
+    _stop: oneshot::Sender<()>,
+}
+
+impl FakeServer {
+    /// Starts a fake server on a random local port, serving accounts from the given fixtures directory.
+    pub async fn start(fixtures_path: &Path) -> Result<Self> {
+        Self::start_with_listener(fixtures_path, None).await
+    }
+
+    /// Starts a fake server using the provided listener (or on a random local port if [`None`]).
+    pub async fn start_with_listener(
+        fixtures_path: &Path,
+        listener: Option<TcpListener>,
+    ) -> Result<Self> {
+        let api = Arc::new(FakeApi::load(fixtures_path)?);
+        let received_requests = Arc::new(Mutex::new(Vec::new()));
+
+        let requests = received_requests.clone();
+        let (started, start_result) = oneshot::channel();
+        let (stop, stopped) = oneshot::channel::<()>();
+        thread::Builder::new()
+            .name("fake-server".into())
+            .spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                {
+                    Ok(runtime) => runtime,
+                    Err(error) => {
+                        started.send(Err(anyhow!(error))).ok();
+                        return;
+                    },
+                };
+                runtime.block_on(async move {
+                    let server = LocalServer::start(listener, "fake server", move |request| {
+                        let request = ReceivedRequest::new(&request);
+                        let response = api.respond(&request);
+                        requests.lock().unwrap().push(request);
+                        async move { response }
+                    });
+                    match server {
+                        Ok(server) => {
+                            started.send(Ok(server.uri())).ok();
+                            stopped.await.ok();
+                        },
+                        Err(error) => {
+                            started.send(Err(error)).ok();
+                        },
+                    }
+                });
+            })
+            .with_context(|| "failed to start fake server thread")?;
+        let uri = start_result
+            .await
+            .with_context(|| "fake server thread exited before starting")??;
+
+        Ok(Self { uri, received_requests, _stop: stop })
+    }
+
+    /// Returns the base URI of the server.
+    ///
+    /// This can be used as the API base URL for both v1 and v2 API clients.
+    pub fn uri(&self) -> String {
+        self.uri.clone()
+    }
+
+    /// Returns the requests received by the server so far, in order.
+    pub fn received_requests(&self) -> Vec<ReceivedRequest> {
+        self.received_requests.lock().unwrap().clone()
+    }
+}
+
+/// Request received by a [`FakeServer`].
+#[derive(Debug, Clone)]
+pub struct ReceivedRequest {
+    /// HTTP method of the request.
+    pub method: Method,
+
+    /// Full URL of the request.
+    pub url: Url,
+
+    /// Headers of the request.
+    pub headers: HeaderMap,
+}
+
+impl ReceivedRequest {
+    fn new(request: &Request<Body>) -> Self {
+        let host = request
+            .headers()
+            .get(HOST)
+            .and_then(|host| host.to_str().ok())
+            .unwrap_or("localhost");
+        let url = Url::parse(&format!("http://{host}{}", request.uri()))
+            .unwrap_or_else(|_| Url::parse("http://localhost/").expect("URL should be valid"));
+
+        Self { method: request.method().clone(), url, headers: request.headers().clone() }
+    }
+
+    fn header(&self, name: impl hyper::header::AsHeaderName) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+}
+
+#[derive(Debug)]
+struct FakeAccount {
+    token: String,
+    solutions: Vec<FakeSolution>,
+}
+
+#[derive(Debug)]
+struct FakeSolution {
+    solution: Value,
+    files: Vec<(String, Vec<u8>)>,
+}
+
+#[derive(Debug)]
+struct FakeApi {
+    accounts: Vec<FakeAccount>,
+}
+
+impl FakeApi {
+    fn load(fixtures_path: &Path) -> Result<Self> {
+        let accounts = sorted_subdirectories(fixtures_path)?
+            .into_iter()
+            .map(|account_path| FakeAccount::load(&account_path))
+            .collect::<Result<Vec<_>>>()
+            .with_context(|| format!("failed to load fixtures from {}", fixtures_path.display()))?;
+
+        Ok(Self { accounts })
+    }
+
+    fn account(&self, request: &ReceivedRequest) -> Option<&FakeAccount> {
+        let token = request.header(AUTHORIZATION)?.strip_prefix("Bearer ")?;
+
+        self.accounts.iter().find(|account| account.token == token)
+    }
+
+    fn get_solutions(&self, account: &FakeAccount, request: &ReceivedRequest) -> Response<Body> {
+        let query_param = |name: &str| {
+            request
+                .url
+                .query_pairs()
+                .find(|(param, _)| param == name)
+                .map(|(_, value)| value.into_owned())
+        };
+        let page = query_param("page")
+            .and_then(|page| page.parse().ok())
+            .unwrap_or(1usize)
+            .max(1);
+        let per_page = query_param("per_page")
+            .and_then(|per_page| per_page.parse().ok())
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .max(1);
+
+        let mut solutions = account
+            .solutions
+            .iter()
+            .filter(|solution| {
+                query_param("track_slug")
+                    .map_or(true, |track| solution.solution["track"]["slug"] == track.as_str())
+            })
+            .collect::<Vec<_>>();
+        match query_param("order").as_deref() {
+            Some("oldest_first") => solutions.sort_by_key(|solution| solution.last_iterated_at()),
+            _ => solutions.sort_by_key(|solution| Reverse(solution.last_iterated_at())),
+        }
+
+        let total_count = solutions.len();
+        let total_pages = ((total_count + per_page - 1) / per_page).max(1);
+        let results = solutions
+            .into_iter()
+            .skip((page - 1) * per_page)
+            .take(per_page)
+            .map(|solution| solution.solution.clone())
+            .collect::<Vec<_>>();
+
+        json_response(request, json!({
+            "results": results,
+            "meta": {
+                "current_page": page,
+                "total_count": total_count,
+                "total_pages": total_pages,
+            },
+        }))
+    }
+
+    fn get_solution(&self, solution: &FakeSolution, request: &ReceivedRequest) -> Response<Body> {
+        let base_url = request.url.origin().ascii_serialization();
+        let track = &solution.solution["track"];
+        let files = solution
+            .files
+            .iter()
+            .map(|(path, _)| path)
+            .collect::<Vec<_>>();
+
+        json_response(request, json!({
+            "solution": {
+                "id": solution.uuid(),
+                "url": solution.solution["private_url"],
+                "team": null,
+                "user": { "handle": "fake-user", "is_requester": true },
+                "exercise": {
+                    "id": solution.solution["exercise"]["slug"],
+                    "instructions_url": solution.solution["private_url"],
+                    "auto_approve": false,
+                    "track": { "id": track["slug"], "language": track["title"] },
+                },
+                "file_download_base_url": format!("{base_url}/solutions/{}/files/", solution.uuid()),
+                "files": files,
+                "iteration": { "submitted_at": solution.last_iterated_at() },
+            },
+        }))
+    }
+
+    fn get_exercises(
+        &self,
+        account: &FakeAccount,
+        track: &str,
+        request: &ReceivedRequest,
+    ) -> Response<Body> {
+        let exercises = account
+            .solutions
+            .iter()
+            .filter(|solution| solution.solution["track"]["slug"] == track)
+            .map(|solution| {
+                let exercise = &solution.solution["exercise"];
+                let slug = exercise["slug"].as_str().unwrap_or_default();
+                json!({
+                    "slug": slug,
+                    "type": solution.solution["exercise_type"].as_str().unwrap_or("practice"),
+                    "title": exercise["title"],
+                    "icon_url": exercise["icon_url"],
+                    "difficulty": "easy",
+                    "blurb": "",
+                    "is_external": false,
+                    "is_unlocked": true,
+                    "is_recommended": false,
+                    "links": { "self": format!("/tracks/{track}/exercises/{slug}") },
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json_response(request, json!({ "exercises": exercises, "solutions": [] }))
+    }
+
+    fn respond(&self, request: &ReceivedRequest) -> Response<Body> {
+        let Some(account) = self.account(request) else {
+            return error_response(
+                StatusCode::UNAUTHORIZED,
+                "invalid_token",
+                "The token provided is invalid",
+            );
+        };
+
+        let segments = request
+            .url
+            .path_segments()
+            .map(|segments| {
+                segments
+                    .filter(|segment| !segment.is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let find_solution = |uuid: &str| {
+            account
+                .solutions
+                .iter()
+                .find(|solution| solution.uuid() == uuid)
+        };
+
+        match segments.as_slice() {
+            ["validate_token"] | ["ping"] => response(StatusCode::OK, &json!({})),
+            ["solutions"] => self.get_solutions(account, request),
+            ["solutions", uuid] => match find_solution(uuid) {
+                Some(solution) => self.get_solution(solution, request),
+                None => {
+                    error_response(StatusCode::NOT_FOUND, "solution_not_found", "Solution not found")
+                },
+            },
+            ["tracks", track, "exercises"] => self.get_exercises(account, track, request),
+            ["solutions", uuid, "files", file_path @ ..] => {
+                let file_path = file_path.join("/");
+                let file_path = percent_decode_str(&file_path).decode_utf8_lossy();
+                find_solution(uuid)
+                    .and_then(|solution| solution.files.iter().find(|(path, _)| *path == file_path))
+                    .map(|(_, content)| Response::new(Body::from(content.clone())))
+                    .unwrap_or_else(|| {
+                        error_response(StatusCode::NOT_FOUND, "file_not_found", "File not found")
+                    })
+            },
+            _ => error_response(StatusCode::NOT_FOUND, "not_found", "Not found"),
+        }
+    }
+}
+
+impl FakeAccount {
+    fn load(account_path: &Path) -> Result<Self> {
+        let token_path = account_path.join("token");
+        let token = std::fs::read_to_string(&token_path)
+            .with_context(|| format!("failed to read token file {}", token_path.display()))?
+            .trim()
+            .to_string();
+        let account_name = file_name(account_path)?;
+
+        let solutions_path = account_path.join("solutions");
+        let mut solutions = Vec::new();
+        if solutions_path.is_dir() {
+            for track_path in sorted_subdirectories(&solutions_path)? {
+                for exercise_path in sorted_subdirectories(&track_path)? {
+                    solutions.push(FakeSolution::load(account_name, &exercise_path)?);
+                }
+            }
+        }
+
+        Ok(Self { token, solutions })
+    }
+}
+
+impl FakeSolution {
+    fn load(account_name: &str, solution_path: &Path) -> Result<Self> {
+        let exercise = file_name(solution_path)?;
+        let track = solution_path
+            .parent()
+            .map(file_name)
+            .transpose()?
+            .unwrap_or_default();
+
+        let solution_json_path = solution_path.join("solution.json");
+        let overrides = match solution_json_path.is_file() {
+            true => serde_json::from_str::<Map<String, Value>>(
+                &std::fs::read_to_string(&solution_json_path)
+                    .with_context(|| format!("failed to read {}", solution_json_path.display()))?,
+            )
+            .with_context(|| {
+                format!("invalid solution JSON in {}", solution_json_path.display())
+            })?,
+            false => Map::new(),
+        };
+
+        let mut solution = default_solution(account_name, track, exercise);
+        if let Value::Object(solution) = &mut solution {
+            solution.extend(overrides);
+        }
+
+        let files_path = solution_path.join("files");
+        let files = match files_path.is_dir() {
+            true => list_files(&files_path)?,
+            false => Vec::new(),
+        };
+
+        Ok(Self { solution, files })
+    }
+
+    fn uuid(&self) -> &str {
+        self.solution["uuid"].as_str().unwrap_or_default()
+    }
+
+    fn last_iterated_at(&self) -> &str {
+        self.solution["last_iterated_at"]
+            .as_str()
+            .unwrap_or_default()
+    }
+}
+
+fn default_solution(account_name: &str, track: &str, exercise: &str) -> Value {
+    let private_url = format!("https://exercism.org/tracks/{track}/exercises/{exercise}");
+
+    json!({
+        "uuid": format!("{account_name}-{track}-{exercise}"),
+        "private_url": private_url,
+        "public_url": format!("https://exercism.org/tracks/{track}/exercises/{exercise}/solutions/{account_name}"),
+        "status": "published",
+        "mentoring_status": "none",
+        "published_iteration_head_tests_status": "passed",
+        "has_notifications": false,
+        "num_views": 0,
+        "num_stars": 0,
+        "num_comments": 0,
+        "num_iterations": 1,
+        "num_loc": 1,
+        "is_out_of_date": false,
+        "published_at": "2024-01-01T00:00:00Z",
+        "completed_at": "2024-01-01T00:00:00Z",
+        "updated_at": "2024-01-01T00:00:00Z",
+        "last_iterated_at": "2024-01-01T00:00:00Z",
+        "exercise": {
+            "slug": exercise,
+            "title": exercise,
+            "icon_url": format!("https://assets.exercism.org/exercises/{exercise}.svg"),
+        },
+        "track": {
+            "slug": track,
+            "title": track,
+            "icon_url": format!("https://assets.exercism.org/tracks/{track}.svg"),
+        },
+    })
+}
+
+/// Returns a JSON response with an `ETag`, or `304 Not Modified` if the request's `If-None-Match` matches it.
+fn json_response(request: &ReceivedRequest, body: Value) -> Response<Body> {
+    let etag = format!("\"{:x}\"", Sha256::digest(body.to_string()));
+
+    let mut response = match request.header(IF_NONE_MATCH) {
+        Some(if_none_match) if if_none_match == etag => Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Body::empty())
+            .expect("response should be valid"),
+        _ => response(StatusCode::OK, &body),
+    };
+    response
+        .headers_mut()
+        .insert(ETAG, etag.parse().expect("ETag should be a valid header value"));
+    response
+}
+
+fn error_response(status: StatusCode, error_type: &str, message: &str) -> Response<Body> {
+    response(status, &json!({ "error": { "type": error_type, "message": message } }))
+}
+
+fn response(status: StatusCode, body: &Value) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("response should be valid")
+}
+
+fn file_name(path: &Path) -> Result<&str> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow!("invalid fixture path {}", path.display()))
+}
+
+fn sorted_subdirectories(path: &Path) -> Result<Vec<PathBuf>> {
+    let mut subdirectories = std::fs::read_dir(path)
+        .with_context(|| format!("failed to list directory {}", path.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()
+        .with_context(|| format!("failed to list directory {}", path.display()))?;
+    subdirectories.retain(|path| path.is_dir());
+    subdirectories.sort();
+
+    Ok(subdirectories)
+}
+
+fn list_files(root: &Path) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut files = Vec::new();
+    let mut directories = vec![root.to_path_buf()];
+
+    while let Some(directory) = directories.pop() {
+        for entry in std::fs::read_dir(&directory)
+            .with_context(|| format!("failed to list directory {}", directory.display()))?
+        {
+            let path = entry
+                .with_context(|| format!("failed to list directory {}", directory.display()))?
+                .path();
+            if path.is_dir() {
+                directories.push(path);
+            } else {
+                let relative_path = path
+                    .strip_prefix(root)
+                    .expect("listed files should be under root")
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                let content = std::fs::read(&path)
+                    .with_context(|| format!("failed to read file {}", path.display()))?;
+                files.push((relative_path, content));
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
@@ -0,0 +1,119 @@
+//! Comparison of two versions of a solution's files, as unified diffs.
+
+use similar::TextDiff;
+
+use crate::local_backup::LocalFile;
+
+/// Change to a single file between two versions of a solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: String,
+    pub diff: FileDiff,
+}
+
+/// Difference between two versions of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileDiff {
+    /// Unified diff of a text file, with `a/` (old) and `b/` (new) headers like `git diff`.
+    ///
+    /// Files that exist in only one version are compared to `/dev/null`.
+    Text(String),
+
+    /// A binary file was added, removed or changed.
+    Binary,
+}
+
+/// Compares two versions of a solution's files.
+///
+/// Only files that differ are returned, sorted by path.
+pub fn diff_files(old: &[LocalFile], new: &[LocalFile]) -> Vec<FileChange> {
+    let mut paths = old
+        .iter()
+        .chain(new)
+        .map(LocalFile::display_path)
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths.dedup();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let old = file_content(old, &path);
+            let new = file_content(new, &path);
+            if old == new {
+                return None;
+            }
+
+            let old_text = old.map(std::str::from_utf8).unwrap_or(Ok(""));
+            let new_text = new.map(std::str::from_utf8).unwrap_or(Ok(""));
+            let diff = match (old_text, new_text) {
+                (Ok(old_text), Ok(new_text)) => {
+                    let old_header =
+                        old.map_or_else(|| "/dev/null".into(), |_| format!("a/{path}"));
+                    let new_header =
+                        new.map_or_else(|| "/dev/null".into(), |_| format!("b/{path}"));
+                    FileDiff::Text(
+                        TextDiff::from_lines(old_text, new_text)
+                            .unified_diff()
+                            .header(&old_header, &new_header)
+                            .to_string(),
+                    )
+                },
+                _ => FileDiff::Binary,
+            };
+
+            Some(FileChange { path, diff })
+        })
+        .collect()
+}
+
+fn file_content<'a>(files: &'a [LocalFile], path: &str) -> Option<&'a [u8]> {
+    files
+        .iter()
+        .find(|file| file.display_path() == path)
+        .map(|file| file.content.as_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, content: &[u8]) -> LocalFile {
+        LocalFile { relative_path: path.into(), content: content.to_vec() }
+    }
+
+    #[test]
+    fn test_diff_files() {
+        let old = [
+            file("src/lib.rs", b"fn main() {}\n"),
+            file("Cargo.toml", b"[package]\n"),
+            file("data.bin", &[0xff]),
+        ];
+        let new = [
+            file("src/lib.rs", b"fn main() { todo!() }\n"),
+            file("Cargo.toml", b"[package]\n"),
+            file("data.bin", &[0xfe]),
+            file("README.md", b"# Poker\n"),
+        ];
+
+        assert_eq!(
+            vec![
+                FileChange {
+                    path: "README.md".into(),
+                    diff: FileDiff::Text(
+                        "--- /dev/null\n+++ b/README.md\n@@ -0,0 +1 @@\n+# Poker\n".into()
+                    ),
+                },
+                FileChange { path: "data.bin".into(), diff: FileDiff::Binary },
+                FileChange {
+                    path: "src/lib.rs".into(),
+                    diff: FileDiff::Text(
+                        "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1 @@\n-fn main() {}\n+fn main() { todo!() }\n"
+                            .into()
+                    ),
+                },
+            ],
+            diff_files(&old, &new)
+        );
+    }
+}
@@ -0,0 +1,529 @@
+//! Rendering of a backup as a static HTML site.
+//!
+//! The site has the following structure:
+//!
+//! ```text
+//! site/
+//! ├── index.html                      Index of tracks
+//! ├── style.css
+//! └── <track>/
+//!     ├── index.html                  List of exercises, with status badges
+//!     └── <exercise>/
+//!         ├── index.html              Solution files and metadata
+//!         └── iterations/
+//!             └── <n>.html            Diff between a previous iteration and the latest one
+//! ```
+//!
+//! When the site is served by the [`Serve`](crate::command::Command::Serve) command, the index
+//! also contains a search form, whose results are rendered by [`render_search`].
+//!
+//! Pages are self-contained: they load no scripts or other resources from outside the site.
+//! Source files are tagged with their language (e.g. `<code class="language-rust">`), so that a
+//! syntax highlighter such as [highlight.js](https://highlightjs.org/) can be added to the site.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use mini_exercism::api::v2::solution::Solution;
+
+use crate::command::backup::args::SolutionStatus;
+use crate::file_diff::{diff_files, FileDiff};
+use crate::local_backup::{LocalFile, LocalIteration, LocalSolution};
+use crate::search::FileMatches;
+
+const STYLE: &str = r#"body { font-family: system-ui, sans-serif; margin: 0 auto; max-width: 60rem; padding: 1rem; color: #222; }
+a { color: #604fcd; text-decoration: none; }
+a:hover { text-decoration: underline; }
+nav { margin-bottom: 1rem; color: #666; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: 0.4rem 0.6rem; border-bottom: 1px solid #ddd; }
+dl { display: grid; grid-template-columns: max-content auto; gap: 0.3rem 1rem; }
+dt { font-weight: bold; }
+dd { margin: 0; }
+pre { border: 1px solid #ddd; border-radius: 4px; overflow-x: auto; }
+pre code { padding: 0.8rem; display: block; }
+.badge { display: inline-block; border-radius: 1rem; padding: 0.1rem 0.6rem; font-size: 0.85rem; color: white; background: #888; }
+.badge.submitted { background: #d38c12; }
+.badge.completed { background: #2e8b57; }
+.badge.published { background: #604fcd; }
+form.search input[type=search] { width: 20rem; padding: 0.3rem; }
+.match { margin: 0.2rem 0; font-family: monospace; white-space: pre-wrap; }
+"#;
+
+/// Page of the site, with its path relative to the site root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// Renders all pages of the site for the given solutions.
+///
+/// If `search` is `true`, the index contains a form to search solutions (see [`render_search`]).
+pub fn render_site(solutions: &[LocalSolution], search: bool) -> Vec<Page> {
+    let tracks = group_by_track(solutions);
+
+    let mut pages = vec![
+        Page { path: "index.html".into(), content: render_index(&tracks, search) },
+        Page { path: "style.css".into(), content: STYLE.into() },
+    ];
+    for (track, solutions) in &tracks {
+        pages.push(Page {
+            path: Path::new(track).join("index.html"),
+            content: render_track(track, solutions),
+        });
+        for solution in solutions {
+            let solution_path = Path::new(track).join(&solution.exercise);
+            pages.push(Page {
+                path: solution_path.join("index.html"),
+                content: render_solution(solution),
+            });
+            pages.extend(solution.iterations.iter().map(|iteration| {
+                Page {
+                    path: solution_path
+                        .join("iterations")
+                        .join(format!("{}.html", iteration.number)),
+                    content: render_iteration_diff(solution, iteration),
+                }
+            }));
+        }
+    }
+
+    pages
+}
+
+/// Renders the page of the site at `path` (relative to the site root, using `/` as separator).
+///
+/// Returns the same content as the matching page of [`render_site`], without rendering the other
+/// pages, or `None` if the site has no page at `path`.
+pub fn render_page(solutions: &[LocalSolution], path: &str, search: bool) -> Option<String> {
+    let find_solution = |track: &str, exercise: &str| {
+        solutions
+            .iter()
+            .find(|solution| solution.track == track && solution.exercise == exercise)
+    };
+
+    match path.split('/').collect::<Vec<_>>()[..] {
+        ["index.html"] => Some(render_index(&group_by_track(solutions), search)),
+        ["style.css"] => Some(STYLE.into()),
+        [track, "index.html"] => {
+            let solutions: Vec<_> = solutions
+                .iter()
+                .filter(|solution| solution.track == track)
+                .collect();
+            (!solutions.is_empty()).then(|| render_track(track, &solutions))
+        },
+        [track, exercise, "index.html"] => find_solution(track, exercise).map(render_solution),
+        [track, exercise, "iterations", file_name] => {
+            let solution = find_solution(track, exercise)?;
+            let iteration = solution
+                .iterations
+                .iter()
+                .find(|iteration| format!("{}.html", iteration.number) == file_name)?;
+            Some(render_iteration_diff(solution, iteration))
+        },
+        _ => None,
+    }
+}
+
+/// Renders the results of a search in the served site.
+pub fn render_search(query: &str, matches: &[FileMatches]) -> String {
+    let mut body = format!(
+        "<nav><a href=\"index.html\">Tracks</a> / Search</nav>\n<h1>Search</h1>\n{}",
+        search_form(query)
+    );
+
+    if !query.is_empty() {
+        let num_lines = matches
+            .iter()
+            .flat_map(|file_matches| &file_matches.lines)
+            .filter(|line| line.is_match)
+            .count();
+        writeln!(body, "<p>{num_lines} matching line(s)</p>").unwrap();
+    }
+    let mut current_solution = None;
+    for file_matches in matches {
+        let solution = (&file_matches.track, &file_matches.exercise);
+        if current_solution != Some(solution) {
+            let track = escape(&file_matches.track);
+            let exercise = escape(&file_matches.exercise);
+            writeln!(
+                body,
+                "<h2><a href=\"{track}/{exercise}/index.html\">{track}/{exercise}</a></h2>"
+            )
+            .unwrap();
+            current_solution = Some(solution);
+        }
+        for line in file_matches.lines.iter().filter(|line| line.is_match) {
+            writeln!(
+                body,
+                "<p class=\"match\">{}:{}: {}</p>",
+                escape(&file_matches.path),
+                line.number,
+                escape(line.text.trim())
+            )
+            .unwrap();
+        }
+    }
+
+    page("Search", "", &body)
+}
+
+fn group_by_track(solutions: &[LocalSolution]) -> BTreeMap<&str, Vec<&LocalSolution>> {
+    let mut tracks: BTreeMap<&str, Vec<&LocalSolution>> = BTreeMap::new();
+    for solution in solutions {
+        tracks.entry(&solution.track).or_default().push(solution);
+    }
+    tracks
+}
+
+fn search_form(query: &str) -> String {
+    format!(
+        "<form class=\"search\" action=\"search.html\"><input type=\"search\" name=\"q\" \
+        value=\"{}\" placeholder=\"Search all solutions\"> <button>Search</button></form>\n",
+        escape(query)
+    )
+}
+
+fn render_index(tracks: &BTreeMap<&str, Vec<&LocalSolution>>, search: bool) -> String {
+    let mut body = String::from("<h1>Exercism solutions</h1>\n");
+    if search {
+        body.push_str(&search_form(""));
+    }
+    if tracks.is_empty() {
+        body.push_str("<p>This backup does not contain any solution.</p>\n");
+    } else {
+        body.push_str("<table>\n<tr><th>Track</th><th>Solutions</th></tr>\n");
+        for (track, solutions) in tracks {
+            let track = escape(track);
+            writeln!(
+                body,
+                "<tr><td><a href=\"{track}/index.html\">{track}</a></td><td>{}</td></tr>",
+                solutions.len()
+            )
+            .unwrap();
+        }
+        body.push_str("</table>\n");
+    }
+
+    page("Exercism solutions", "", &body)
+}
+
+fn render_track(track: &str, solutions: &[&LocalSolution]) -> String {
+    let track = escape(track);
+    let mut body = format!(
+        "<nav><a href=\"../index.html\">Tracks</a> / {track}</nav>\n<h1>{track}</h1>\n\
+        <table>\n<tr><th>Exercise</th><th>Status</th><th>Iterations</th></tr>\n"
+    );
+    for solution in solutions {
+        let metadata = solution.metadata.as_ref();
+        let exercise = escape(&solution.exercise);
+        writeln!(
+            body,
+            "<tr><td><a href=\"{exercise}/index.html\">{exercise}</a></td><td>{}</td><td>{}</td></tr>",
+            status_badge(metadata),
+            metadata.map_or_else(String::new, |metadata| metadata.num_iterations.to_string()),
+        )
+        .unwrap();
+    }
+    body.push_str("</table>\n");
+
+    page(&track, "../", &body)
+}
+
+fn render_solution(solution: &LocalSolution) -> String {
+    let track = escape(&solution.track);
+    let exercise = escape(&solution.exercise);
+    let mut body = format!(
+        "<nav><a href=\"../../index.html\">Tracks</a> / <a href=\"../index.html\">{track}</a> / \
+        {exercise}</nav>\n<h1>{exercise}</h1>\n"
+    );
+
+    if let Some(metadata) = &solution.metadata {
+        body.push_str("<dl>\n");
+        let mut item = |name: &str, value: String| {
+            writeln!(body, "<dt>{name}</dt><dd>{value}</dd>").unwrap();
+        };
+        item("Status", status_badge(Some(metadata)));
+        item("Iterations", metadata.num_iterations.to_string());
+        item("Mentoring", escape(&enum_name(&metadata.mentoring_status)));
+        for (name, date) in [
+            ("Last iterated", &metadata.last_iterated_at),
+            ("Completed", &metadata.completed_at),
+            ("Published", &metadata.published_at),
+        ] {
+            if let Some(date) = date {
+                item(name, escape(date.get(..10).unwrap_or(date)));
+            }
+        }
+        let url = match metadata.published_at {
+            Some(_) => &metadata.public_url,
+            None => &metadata.private_url,
+        };
+        item("On Exercism", format!("<a href=\"{0}\">{0}</a>", escape(url)));
+        body.push_str("</dl>\n");
+    }
+
+    if !solution.iterations.is_empty() {
+        body.push_str("<h2>Previous iterations</h2>\n<ul>\n");
+        for iteration in &solution.iterations {
+            writeln!(
+                body,
+                "<li><a href=\"iterations/{0}.html\">Iteration {0}</a> (changes since then)</li>",
+                iteration.number
+            )
+            .unwrap();
+        }
+        body.push_str("</ul>\n");
+    }
+
+    if solution.files.is_empty() {
+        body.push_str("<p>This solution does not contain any file.</p>\n");
+    }
+    for file in &solution.files {
+        render_file(&mut body, file);
+    }
+
+    page(&format!("{track}/{exercise}"), "../../", &body)
+}
+
+fn render_iteration_diff(solution: &LocalSolution, iteration: &LocalIteration) -> String {
+    let track = escape(&solution.track);
+    let exercise = escape(&solution.exercise);
+    let mut body = format!(
+        "<nav><a href=\"../../../index.html\">Tracks</a> / <a href=\"../../index.html\">{track}</a> \
+        / <a href=\"../index.html\">{exercise}</a> / Iteration {0}</nav>\n\
+        <h1>{exercise}: changes since iteration {0}</h1>\n",
+        iteration.number
+    );
+
+    let changes = diff_files(&iteration.files, &solution.files);
+    for change in &changes {
+        writeln!(body, "<h2>{}</h2>", escape(&change.path)).unwrap();
+        match &change.diff {
+            FileDiff::Text(diff) => {
+                writeln!(body, "<pre><code class=\"language-diff\">{}</code></pre>", escape(diff))
+                    .unwrap();
+            },
+            FileDiff::Binary => body.push_str("<p>Binary file changed</p>\n"),
+        }
+    }
+    if changes.is_empty() {
+        body.push_str("<p>No changes since this iteration.</p>\n");
+    }
+
+    page(&format!("{track}/{exercise}: iteration {}", iteration.number), "../../../", &body)
+}
+
+fn render_file(body: &mut String, file: &LocalFile) {
+    writeln!(body, "<h2>{}</h2>", escape(&file.display_path())).unwrap();
+
+    match std::str::from_utf8(&file.content) {
+        Ok(text) => {
+            let class = language(&file.relative_path)
+                .map(|language| format!(" class=\"language-{language}\""))
+                .unwrap_or_default();
+            writeln!(body, "<pre><code{class}>{}</code></pre>", escape(text)).unwrap();
+        },
+        Err(_) => {
+            writeln!(body, "<p>Binary file ({} bytes)</p>", file.content.len()).unwrap();
+        },
+    }
+}
+
+fn page(title: &str, root: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+        <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+        <title>{title}</title>\n<link rel=\"stylesheet\" href=\"{root}style.css\">\n\
+        </head>\n<body>\n{body}</body>\n</html>\n"
+    )
+}
+
+fn status_badge(metadata: Option<&Solution>) -> String {
+    let status = metadata
+        .and_then(|metadata| SolutionStatus::try_from(metadata.status).ok())
+        .and_then(|status| clap::ValueEnum::to_possible_value(&status))
+        .map_or_else(|| "unknown".to_string(), |value| value.get_name().to_string());
+
+    format!("<span class=\"badge {status}\">{status}</span>")
+}
+
+/// Returns the serialized name of an enum value, in a human-readable form (e.g. `in progress`).
+fn enum_name<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(|name| name.replace('_', " ")))
+        .unwrap_or_else(|| "unknown".into())
+}
+
+/// Returns the language of a file, as named by highlight.js, if known.
+fn language(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?;
+    Some(match extension {
+        "rs" => "rust",
+        "ex" | "exs" => "elixir",
+        "py" => "python",
+        "js" | "mjs" => "javascript",
+        "ts" => "typescript",
+        "rb" => "ruby",
+        "go" => "go",
+        "java" => "java",
+        "kt" | "kts" => "kotlin",
+        "cs" => "csharp",
+        "fs" => "fsharp",
+        "c" | "h" => "c",
+        "cpp" | "hpp" | "cc" => "cpp",
+        "hs" => "haskell",
+        "clj" | "cljs" => "clojure",
+        "scala" => "scala",
+        "swift" => "swift",
+        "php" => "php",
+        "sh" | "bash" => "bash",
+        "toml" => "ini",
+        "json" => "json",
+        "md" => "markdown",
+        "yml" | "yaml" => "yaml",
+        "xml" => "xml",
+        "html" => "html",
+        "css" => "css",
+        _ => return None,
+    })
+}
+
+/// Escapes text for use in HTML content or (quoted) attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::SearchLine;
+
+    fn solution(track: &str, exercise: &str, files: &[(&str, &[u8])]) -> LocalSolution {
+        LocalSolution {
+            track: track.into(),
+            exercise: exercise.into(),
+            metadata: None,
+            files: files
+                .iter()
+                .map(|(path, content)| LocalFile {
+                    relative_path: path.into(),
+                    content: content.to_vec(),
+                })
+                .collect(),
+            iterations: vec![],
+        }
+    }
+
+    #[test]
+    fn test_render_site() {
+        let mut poker = solution("rust", "poker", &[("src/lib.rs", b"fn main() { 1 < 2; }\n")]);
+        poker.iterations.push(LocalIteration {
+            number: 1,
+            files: vec![LocalFile {
+                relative_path: "src/lib.rs".into(),
+                content: b"fn main() {}\n".to_vec(),
+            }],
+        });
+        let pages = render_site(
+            &[poker, solution("rust", "hello-world", &[("data.bin", &[0xff, 0x00])])],
+            false,
+        );
+
+        assert_eq!(
+            vec![
+                "index.html",
+                "style.css",
+                "rust/index.html",
+                "rust/poker/index.html",
+                "rust/poker/iterations/1.html",
+                "rust/hello-world/index.html",
+            ],
+            pages
+                .iter()
+                .map(|page| page.path.to_string_lossy().replace('\\', "/"))
+                .collect::<Vec<_>>()
+        );
+        assert!(pages[0]
+            .content
+            .contains("<a href=\"rust/index.html\">rust</a></td><td>2</td>"));
+        assert!(pages[2]
+            .content
+            .contains("<span class=\"badge unknown\">unknown</span>"));
+        assert!(pages[3]
+            .content
+            .contains("<pre><code class=\"language-rust\">fn main() { 1 &lt; 2; }\n</code></pre>"));
+        assert!(pages[3]
+            .content
+            .contains("<a href=\"iterations/1.html\">Iteration 1</a>"));
+        assert!(pages[4]
+            .content
+            .contains("-fn main() {}\n+fn main() { 1 &lt; 2; }\n"));
+        assert!(pages[5].content.contains("Binary file (2 bytes)"));
+        assert!(!pages[0].content.contains("<form"));
+        assert!(pages.iter().all(|page| !page.content.contains("<script")));
+    }
+
+    #[test]
+    fn test_render_page() {
+        let mut poker = solution("rust", "poker", &[("src/lib.rs", b"fn main() {}\n")]);
+        poker
+            .iterations
+            .push(LocalIteration { number: 1, files: vec![] });
+        let solutions =
+            [poker, solution("rust", "leap", &[]), solution("python", "leap", &[("leap.py", b"")])];
+
+        for page in render_site(&solutions, true) {
+            let path = page.path.to_string_lossy().replace('\\', "/");
+            assert_eq!(Some(page.content), render_page(&solutions, &path, true), "{path}");
+        }
+        for path in
+            ["", "go/index.html", "rust/poker", "rust/poker/iterations/2.html", "../index.html"]
+        {
+            assert_eq!(None, render_page(&solutions, path, true), "{path}");
+        }
+    }
+
+    #[test]
+    fn test_render_search() {
+        let file_matches = |exercise: &str, numbers: &[usize]| FileMatches {
+            track: "rust".into(),
+            exercise: exercise.into(),
+            iteration: None,
+            path: "src/lib.rs".into(),
+            lines: numbers
+                .iter()
+                .map(|&number| SearchLine { number, text: "    Vec<u8>".into(), is_match: true })
+                .collect(),
+        };
+
+        let page =
+            render_search("u8", &[file_matches("poker", &[1, 5]), file_matches("leap", &[2])]);
+        assert!(page.contains("value=\"u8\""));
+        assert!(page.contains("<p>3 matching line(s)</p>"));
+        assert_eq!(2, page.matches("<h2>").count());
+        assert!(page.contains("<p class=\"match\">src/lib.rs:5: Vec&lt;u8&gt;</p>"));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            "&lt;a href=&quot;x&quot;&gt;R&amp;D&#39;s&lt;/a&gt;",
+            escape("<a href=\"x\">R&D's</a>")
+        );
+    }
+}
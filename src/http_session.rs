@@ -0,0 +1,418 @@
+//! Recording and replay of the HTTP sessions between `exsb` and the Exercism API.
+//!
+//! When recording, the API clients are pointed at a local [`Recorder`] proxy that forwards every
+//! request to the real Exercism API and stores each exchange in the session directory:
+//!
+//! ```text
+//! session/
+//! ├── 0001.json       Request method, path and query, response status and content type
+//! ├── 0001.body       Raw response body
+//! ├── 0002.json
+//! └── ...
+//! ```
+//!
+//! Request headers are never recorded, and the API token is redacted from response bodies, so a
+//! session directory can safely be attached to a bug report. A [`Replayer`] then serves the
+//! recorded responses back, without needing network access or a valid token.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Context};
+use hyper::header::{HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE};
+use hyper::{Body, Request, Response, StatusCode};
+use mini_exercism::api::v1::DEFAULT_V1_API_BASE_URL;
+use mini_exercism::api::v2::DEFAULT_V2_API_BASE_URL;
+use serde_json::{json, Value};
+use tracing::{debug, info, warn};
+
+use crate::cache::HttpCache;
+use crate::command::backup::args::BackupArgs;
+use crate::local_server::LocalServer;
+use crate::Result;
+
+/// Placeholder written in recorded bodies instead of the API token.
+pub const REDACTED_TOKEN: &str = "[REDACTED]";
+
+/// HTTP session recorded, replayed or cached while running a command.
+///
+/// The session ends when dropped.
+#[derive(Debug)]
+pub enum HttpSession {
+    Record(Recorder),
+    Replay(Replayer),
+    Cache(HttpCache),
+}
+
+impl HttpSession {
+    /// Starts the session requested by the `--record` or `--replay` options, if any.
+    ///
+    /// Otherwise, API responses are cached unless disabled with `--no-cache` (see [`HttpCache`]).
+    pub async fn start(args: &BackupArgs) -> Result<Option<Self>> {
+        Ok(if let Some(session_path) = &args.record {
+            let http_client = args.http.build_http_client()?;
+            let recorder =
+                Recorder::start(session_path, http_client, args.api_base_url.as_deref()).await?;
+            info!("Recording HTTP session to {}", session_path.display());
+            Some(Self::Record(recorder))
+        } else if let Some(session_path) = &args.replay {
+            let replayer = Replayer::start(session_path).await?;
+            info!("Replaying HTTP session from {}", session_path.display());
+            Some(Self::Replay(replayer))
+        } else {
+            let http_client = args.http.build_http_client()?;
+            HttpCache::start(&args.cache, http_client, args.api_base_url.as_deref())
+                .await?
+                .map(Self::Cache)
+        })
+    }
+
+    /// Returns the URI to use as the API base URL for both v1 and v2 API clients.
+    pub fn uri(&self) -> String {
+        match self {
+            Self::Record(recorder) => recorder.uri(),
+            Self::Replay(replayer) => replayer.uri(),
+            Self::Cache(cache) => cache.uri(),
+        }
+    }
+}
+
+/// Local proxy recording exchanges with the Exercism API.
+#[derive(Debug)]
+pub struct Recorder {
+    server: LocalServer,
+}
+
+impl Recorder {
+    /// Starts a recording proxy on a random local port, storing exchanges in `session_path`.
+    ///
+    /// Requests are forwarded to `api_base_url` if specified; otherwise, they are forwarded to the
+    /// v1 or v2 Exercism API depending on the endpoint (see [`upstream_base_url`]).
+    pub async fn start(
+        session_path: &Path,
+        http_client: reqwest::Client,
+        api_base_url: Option<&str>,
+    ) -> Result<Self> {
+        tokio::fs::create_dir_all(session_path)
+            .await
+            .with_context(|| {
+                format!("failed to create session directory {}", session_path.display())
+            })?;
+
+        let proxy = Arc::new(RecordingProxy {
+            session_path: session_path.to_path_buf(),
+            http_client,
+            api_base_url: api_base_url.map(|url| url.trim_end_matches('/').to_string()),
+            next_index: AtomicUsize::new(1),
+        });
+
+        let server = LocalServer::start(None, "recording proxy", move |request| {
+            proxy.clone().forward(request)
+        })?;
+
+        Ok(Self { server })
+    }
+
+    /// Returns the base URI of the proxy.
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+}
+
+#[derive(Debug)]
+struct RecordingProxy {
+    session_path: PathBuf,
+    http_client: reqwest::Client,
+    api_base_url: Option<String>,
+    next_index: AtomicUsize,
+}
+
+impl RecordingProxy {
+    async fn forward(self: Arc<Self>, request: Request<Body>) -> Response<Body> {
+        self.record(request).await.unwrap_or_else(|error| {
+            warn!("Failed to record exchange: {error:#}");
+            Response::builder()
+                .status(StatusCode::BAD_GATEWAY)
+                .body(Body::from(format!("{error:#}")))
+                .expect("error response should be valid")
+        })
+    }
+
+    async fn record(&self, request: Request<Body>) -> Result<Response<Body>> {
+        let index = self.next_index.fetch_add(1, Ordering::SeqCst);
+        let method = request.method().clone();
+        let path = request.uri().path().to_string();
+        let query = request.uri().query().map(str::to_string);
+        let token = request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::to_string);
+
+        let base_url = match &self.api_base_url {
+            Some(api_base_url) => api_base_url.as_str(),
+            None => upstream_base_url(&path),
+        };
+        let url = match &query {
+            Some(query) => format!("{base_url}{path}?{query}"),
+            None => format!("{base_url}{path}"),
+        };
+        debug!(index, %method, %url, "Recording exchange");
+
+        let mut upstream_request = self.http_client.request(method.clone(), &url);
+        for header in [AUTHORIZATION, ACCEPT] {
+            if let Some(value) = request.headers().get(&header) {
+                upstream_request = upstream_request.header(header, value);
+            }
+        }
+        let request_body = hyper::body::to_bytes(request.into_body())
+            .await
+            .with_context(|| format!("failed to read request body for {url}"))?;
+        let upstream_response = upstream_request
+            .body(request_body)
+            .send()
+            .await
+            .with_context(|| format!("failed to forward request to {url}"))?;
+
+        let status = upstream_response.status();
+        let content_type = upstream_response.headers().get(CONTENT_TYPE).cloned();
+        let body = upstream_response
+            .bytes()
+            .await
+            .with_context(|| format!("failed to read response body from {url}"))?;
+
+        let metadata = json!({
+            "method": method.as_str(),
+            "path": path,
+            "query": query,
+            "status": status.as_u16(),
+            "content_type": content_type.as_ref().and_then(|value| value.to_str().ok()),
+        });
+        self.write_exchange(index, &metadata, &redact(&body, token.as_deref()))
+            .await?;
+
+        let mut response = Response::builder().status(status);
+        if let Some(content_type) = content_type {
+            response = response.header(CONTENT_TYPE, content_type);
+        }
+        response
+            .body(Body::from(body))
+            .with_context(|| format!("failed to build response for {url}"))
+    }
+
+    async fn write_exchange(&self, index: usize, metadata: &Value, body: &[u8]) -> Result<()> {
+        let metadata_path = self.session_path.join(format!("{index:04}.json"));
+        let body_path = metadata_path.with_extension("body");
+
+        let metadata = serde_json::to_vec_pretty(metadata)
+            .with_context(|| format!("failed to serialize exchange {index}"))?;
+        tokio::fs::write(&metadata_path, metadata)
+            .await
+            .with_context(|| format!("failed to write {}", metadata_path.display()))?;
+        tokio::fs::write(&body_path, body)
+            .await
+            .with_context(|| format!("failed to write {}", body_path.display()))
+    }
+}
+
+/// Returns the base URL of the Exercism API serving the given endpoint.
+///
+/// The v1 and v2 endpoints used by `exsb` do not overlap: only listing solutions and getting
+/// track information use the v2 API.
+pub fn upstream_base_url(path: &str) -> &'static str {
+    let path = path.trim_end_matches('/');
+    if path == "/solutions" || path.starts_with("/tracks") {
+        DEFAULT_V2_API_BASE_URL
+    } else {
+        DEFAULT_V1_API_BASE_URL
+    }
+}
+
+/// Replaces every occurrence of `token` in `body` with [`REDACTED_TOKEN`].
+fn redact(body: &[u8], token: Option<&str>) -> Vec<u8> {
+    let Some(token) = token.map(str::as_bytes).filter(|token| !token.is_empty()) else {
+        return body.to_vec();
+    };
+
+    let mut redacted = Vec::with_capacity(body.len());
+    let mut remaining = body;
+    while !remaining.is_empty() {
+        if remaining.starts_with(token) {
+            redacted.extend_from_slice(REDACTED_TOKEN.as_bytes());
+            remaining = &remaining[token.len()..];
+        } else {
+            redacted.push(remaining[0]);
+            remaining = &remaining[1..];
+        }
+    }
+    redacted
+}
+
+/// Server replaying exchanges previously stored by a [`Recorder`].
+///
+/// Requests are matched on their method, path and query. If the same request was recorded
+/// multiple times, responses are replayed in order, the last one being repeated afterwards.
+/// The server is stopped when dropped.
+#[derive(Debug)]
+pub struct Replayer {
+    server: LocalServer,
+}
+
+impl Replayer {
+    /// Starts a replay server on a random local port, serving exchanges from `session_path`.
+    pub async fn start(session_path: &Path) -> Result<Self> {
+        let session =
+            Arc::new(RecordedSession::load(session_path).with_context(|| {
+                format!("failed to load session from {}", session_path.display())
+            })?);
+
+        let server = LocalServer::start(None, "replay server", move |request| {
+            let response = session.respond(&request);
+            async move { response }
+        })?;
+
+        Ok(Self { server })
+    }
+
+    /// Returns the base URI of the server.
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+}
+
+type ExchangeKey = (String, String, Option<String>);
+
+#[derive(Debug, Clone)]
+struct RecordedResponse {
+    status: StatusCode,
+    content_type: Option<HeaderValue>,
+    body: Vec<u8>,
+}
+
+#[derive(Debug)]
+struct RecordedSession {
+    exchanges: Mutex<HashMap<ExchangeKey, Vec<RecordedResponse>>>,
+}
+
+impl RecordedSession {
+    fn load(session_path: &Path) -> Result<Self> {
+        let mut metadata_paths = std::fs::read_dir(session_path)
+            .with_context(|| format!("failed to list directory {}", session_path.display()))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()
+            .with_context(|| format!("failed to list directory {}", session_path.display()))?;
+        metadata_paths.retain(|path| path.extension().map_or(false, |ext| ext == "json"));
+        metadata_paths.sort();
+
+        if metadata_paths.is_empty() {
+            return Err(anyhow!("no recorded exchanges found"));
+        }
+
+        let mut exchanges: HashMap<_, Vec<_>> = HashMap::new();
+        for metadata_path in metadata_paths {
+            let (key, response) = Self::load_exchange(&metadata_path)
+                .with_context(|| format!("invalid exchange {}", metadata_path.display()))?;
+            exchanges.entry(key).or_default().push(response);
+        }
+
+        Ok(Self { exchanges: Mutex::new(exchanges) })
+    }
+
+    fn load_exchange(metadata_path: &Path) -> Result<(ExchangeKey, RecordedResponse)> {
+        let metadata: Value = serde_json::from_slice(
+            &std::fs::read(metadata_path)
+                .with_context(|| format!("failed to read {}", metadata_path.display()))?,
+        )?;
+        let body_path = metadata_path.with_extension("body");
+        let body = std::fs::read(&body_path)
+            .with_context(|| format!("failed to read {}", body_path.display()))?;
+
+        let field = |name: &str| metadata[name].as_str().map(str::to_string);
+        let key = (
+            field("method").ok_or_else(|| anyhow!("missing method"))?,
+            field("path").ok_or_else(|| anyhow!("missing path"))?,
+            field("query"),
+        );
+        let status = metadata["status"]
+            .as_u64()
+            .and_then(|status| u16::try_from(status).ok())
+            .and_then(|status| StatusCode::from_u16(status).ok())
+            .ok_or_else(|| anyhow!("missing or invalid status"))?;
+        let content_type = field("content_type")
+            .map(HeaderValue::try_from)
+            .transpose()
+            .with_context(|| "invalid content type")?;
+
+        Ok((key, RecordedResponse { status, content_type, body }))
+    }
+}
+
+impl RecordedSession {
+    fn respond(&self, request: &Request<Body>) -> Response<Body> {
+        let key = (
+            request.method().to_string(),
+            request.uri().path().to_string(),
+            request.uri().query().map(str::to_string),
+        );
+
+        let response = {
+            let mut exchanges = self.exchanges.lock().unwrap();
+            exchanges
+                .get_mut(&key)
+                .map(|responses| match responses.len() {
+                    1 => responses[0].clone(),
+                    _ => responses.remove(0),
+                })
+        };
+
+        let (status, content_type, body) = match response {
+            Some(RecordedResponse { status, content_type, body }) => (status, content_type, body),
+            None => {
+                warn!("No recorded response for {} {}", key.0, request.uri());
+                let body = json!({
+                    "error": {
+                        "type": "not_recorded",
+                        "message": "No recorded response for this request",
+                    }
+                });
+                let content_type = HeaderValue::from_static("application/json");
+                (StatusCode::NOT_FOUND, Some(content_type), body.to_string().into_bytes())
+            },
+        };
+
+        let mut response = Response::builder().status(status);
+        if let Some(content_type) = content_type {
+            response = response.header(CONTENT_TYPE, content_type);
+        }
+        response
+            .body(Body::from(body))
+            .expect("recorded response should be valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(
+            b"token: [REDACTED], again: [REDACTED]!".to_vec(),
+            redact(b"token: abc123, again: abc123!", Some("abc123"))
+        );
+        assert_eq!(b"no token".to_vec(), redact(b"no token", None));
+        assert_eq!(b"empty".to_vec(), redact(b"empty", Some("")));
+    }
+
+    #[test]
+    fn test_upstream_base_url() {
+        assert_eq!(DEFAULT_V2_API_BASE_URL, upstream_base_url("/solutions"));
+        assert_eq!(DEFAULT_V2_API_BASE_URL, upstream_base_url("/tracks/rust/exercises"));
+        assert_eq!(DEFAULT_V1_API_BASE_URL, upstream_base_url("/solutions/1234"));
+        assert_eq!(DEFAULT_V1_API_BASE_URL, upstream_base_url("/solutions/1234/files/src/lib.rs"));
+        assert_eq!(DEFAULT_V1_API_BASE_URL, upstream_base_url("/validate_token"));
+    }
+}
@@ -0,0 +1,109 @@
+//! List of solutions known to exist in Exercism accounts, used to complete track and exercise names.
+//!
+//! The list is stored in `exsb`'s [cache directory](cache_dir), and is updated by commands that fetch the
+//! list of solutions from the Exercism API (like `backup`). Solutions are only ever added to the
+//! list, since different commands can see different subsets of solutions.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use mini_exercism::api::v2::solution::Solution;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tracing::debug;
+
+use crate::cache::{cache_dir, CACHE_DIR_ENV_VAR};
+use crate::Result;
+
+const KNOWN_SOLUTIONS_FILE: &str = "known-solutions.json";
+
+/// Solution known to exist in an Exercism account.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct KnownSolution {
+    pub track: String,
+    pub exercise: String,
+}
+
+impl From<&Solution> for KnownSolution {
+    fn from(solution: &Solution) -> Self {
+        Self { track: solution.track.name.clone(), exercise: solution.exercise.name.clone() }
+    }
+}
+
+/// Loads the list of known solutions, sorted by track, then exercise.
+///
+/// If the list has never been saved or cannot be read, an empty list is returned.
+pub fn load() -> BTreeSet<KnownSolution> {
+    match known_solutions_path() {
+        Ok(path) => parse(&path, std::fs::read(&path)),
+        Err(error) => {
+            debug!("{error:#}");
+            BTreeSet::new()
+        },
+    }
+}
+
+/// Adds solutions to the list of known solutions.
+///
+/// The list is replaced atomically, so that it is never left partially written. Since it is only
+/// used for completion, errors are logged and otherwise ignored.
+pub async fn add<I>(solutions: I)
+where
+    I: IntoIterator<Item = KnownSolution>,
+{
+    if let Err(error) = try_add(solutions).await {
+        debug!("Failed to update list of known solutions: {error:#}");
+    }
+}
+
+async fn try_add<I>(solutions: I) -> Result<()>
+where
+    I: IntoIterator<Item = KnownSolution>,
+{
+    let path = known_solutions_path()?;
+    let mut known_solutions = parse(&path, fs::read(&path).await);
+    let num_known = known_solutions.len();
+    known_solutions.extend(solutions);
+    if known_solutions.len() == num_known {
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("failed to create directory {}", parent.display()))?;
+    }
+    let content = serde_json::to_vec_pretty(&known_solutions)
+        .with_context(|| "failed to serialize list of known solutions")?;
+
+    // Other `exsb` processes could be updating the list at the same time.
+    let temp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+    fs::write(&temp_path, content)
+        .await
+        .with_context(|| format!("failed to write file {}", temp_path.display()))?;
+    fs::rename(&temp_path, &path)
+        .await
+        .with_context(|| format!("failed to replace file {}", path.display()))
+}
+
+/// Parses the content of the list of known solutions, ignoring invalid or unreadable lists.
+fn parse(path: &Path, content: std::io::Result<Vec<u8>>) -> BTreeSet<KnownSolution> {
+    let result = match content {
+        Ok(content) => serde_json::from_slice(&content)
+            .with_context(|| format!("invalid list of known solutions in {}", path.display())),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(BTreeSet::new()),
+        Err(error) => Err(error).with_context(|| format!("failed to read file {}", path.display())),
+    };
+
+    result.unwrap_or_else(|error| {
+        debug!("Ignoring list of known solutions: {error:#}");
+        BTreeSet::new()
+    })
+}
+
+fn known_solutions_path() -> Result<PathBuf> {
+    cache_dir()
+        .map(|cache_dir| cache_dir.join(KNOWN_SOLUTIONS_FILE))
+        .ok_or_else(|| anyhow!("failed to determine cache directory; set {CACHE_DIR_ENV_VAR}"))
+}
@@ -6,13 +6,27 @@
 #![deny(rustdoc::private_intra_doc_links)]
 #![cfg_attr(any(nightly_rustc, docsrs), feature(doc_cfg))]
 
+pub(crate) mod cache;
 pub mod command;
+pub(crate) mod credentials;
+pub(crate) mod crypto;
 pub(crate) mod download_limiter;
 pub mod error;
+pub mod fake_server;
+pub(crate) mod file_diff;
+pub(crate) mod html_site;
+pub(crate) mod http_session;
+pub(crate) mod known_solutions;
+pub(crate) mod local_backup;
+pub(crate) mod local_server;
+pub(crate) mod search;
 pub(crate) mod task_pool;
 
+use std::fs::OpenOptions;
 use std::str::FromStr;
+use std::sync::Mutex;
 
+use anyhow::Context;
 use clap::Parser;
 use clap_verbosity_flag::{InfoLevel, Verbosity};
 pub use error::Error;
@@ -60,6 +74,9 @@ impl Cli {
     ///   This can also be used to enable tracing for other modules (e.g. setting the environment
     ///   variable to `trace` will print everything, from all crates involved).
     ///
+    /// Logs are written to the standard error, unless the command specifies a log file (see
+    /// [`Command::log_file`]).
+    ///
     /// [`env_logger`]: https://docs.rs/env_logger/latest/env_logger/
     pub async fn execute() -> Result<()> {
         let cli = Self::parse();
@@ -70,7 +87,22 @@ impl Cli {
         let env_filter = EnvFilter::builder()
             .with_default_directive(default_directive)
             .from_env_lossy();
-        tracing_subscriber::fmt().with_env_filter(env_filter).init();
+        // Logs go to stderr so that commands outputting data (like `stats`) can be piped.
+        let subscriber = tracing_subscriber::fmt().with_env_filter(env_filter);
+        match cli.command.log_file() {
+            Some(log_file) => {
+                let log_file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(log_file)
+                    .with_context(|| format!("failed to open log file {}", log_file.display()))?;
+                subscriber
+                    .with_ansi(false)
+                    .with_writer(Mutex::new(log_file))
+                    .init();
+            },
+            None => subscriber.with_writer(std::io::stderr).init(),
+        }
 
         cli.command.execute().await
     }
@@ -0,0 +1,433 @@
+//! Reading of backups created by the [`Backup`](crate::command::Command::Backup) command.
+//!
+//! Backups are stored using the following layout:
+//!
+//! ```text
+//! backup/
+//! └── <track>/
+//!     └── <exercise>/
+//!         ├── .exsb/
+//!         │   ├── solution.json       Metadata of the solution, as returned by the v2 API
+//!         │   ├── SHA256SUMS          Checksums of the solution's files, in `sha256sum` format
+//!         │   └── iterations/
+//!         │       └── <n>/            Files of a previous iteration (see `--keep-iterations`)
+//!         └── ...                     Files of the solution's latest iteration
+//! ```
+//!
+//! The metadata directory is only present for solutions backed up by recent versions of `exsb`.
+//! All files (including metadata) can be encrypted (see [`Cipher`]), except checksums, which are
+//! those of the files as stored on disk (so they can be checked with `sha256sum -c`). Files and
+//! directories of a solution have their modification time set to the solution's submission time
+//! (see [`submission_time`]), except previous iterations, which keep theirs; track directories
+//! (and account directories when backing up multiple accounts) then get the latest modification
+//! time of their subdirectories. Backing up the same solutions again thus results in an identical
+//! tree, but not in identical encrypted files: each encryption uses a random salt and nonce.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{anyhow, Context};
+use chrono::DateTime;
+use filetime::FileTime;
+use mini_exercism::api::v2::solution::Solution;
+use sha2::{Digest, Sha256};
+use tokio::fs;
+
+use crate::crypto::Cipher;
+use crate::Result;
+
+/// Name of the directory storing `exsb` metadata in each solution directory.
+pub const METADATA_DIR: &str = ".exsb";
+
+/// Name of the file storing the solution's metadata in the [`METADATA_DIR`].
+pub const SOLUTION_METADATA_FILE: &str = "solution.json";
+
+/// Name of the file storing checksums of the solution's files in the [`METADATA_DIR`].
+pub const CHECKSUMS_FILE: &str = "SHA256SUMS";
+
+/// Name of the directory storing previous iterations in the [`METADATA_DIR`].
+pub const ITERATIONS_DIR: &str = "iterations";
+
+/// Solution read from a local backup.
+#[derive(Debug, Clone)]
+pub struct LocalSolution {
+    pub track: String,
+    pub exercise: String,
+    pub metadata: Option<Solution>,
+    pub files: Vec<LocalFile>,
+    pub iterations: Vec<LocalIteration>,
+}
+
+/// Previous iteration of a [`LocalSolution`], archived by the backup command.
+#[derive(Debug, Clone)]
+pub struct LocalIteration {
+    pub number: u32,
+    pub files: Vec<LocalFile>,
+}
+
+/// File of a [`LocalSolution`], decrypted if needed.
+#[derive(Debug, Clone)]
+pub struct LocalFile {
+    pub relative_path: PathBuf,
+    pub content: Vec<u8>,
+}
+
+impl LocalFile {
+    /// Returns the path of this file, relative to its solution, using `/` as separator.
+    pub fn display_path(&self) -> String {
+        self.relative_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Returns the number of lines in this file, or [`None`] if it's not a text file.
+    pub fn lines(&self) -> Option<u64> {
+        std::str::from_utf8(&self.content)
+            .ok()
+            .map(|text| text.lines().count() as u64)
+    }
+}
+
+/// Reads all solutions stored in the backup at `root`.
+///
+/// Encrypted files are decrypted using `cipher`; if it is [`None`], reading an encrypted file
+/// fails. Solutions are returned sorted by track, then exercise.
+pub async fn read_solutions(root: &Path, cipher: Option<&Cipher>) -> Result<Vec<LocalSolution>> {
+    let mut solutions = Vec::new();
+
+    for track in list_subdirectories(root).await? {
+        for exercise in list_subdirectories(&root.join(&track)).await? {
+            solutions.push(read_solution(root, &track, &exercise, cipher).await?);
+        }
+    }
+
+    Ok(solutions)
+}
+
+/// Reads the solution to `track`/`exercise` stored in the backup at `root`.
+///
+/// Encrypted files are decrypted using `cipher` (see [`read_solutions`]).
+pub async fn read_solution(
+    root: &Path,
+    track: &str,
+    exercise: &str,
+    cipher: Option<&Cipher>,
+) -> Result<LocalSolution> {
+    let path = root.join(track).join(exercise);
+    if !fs::metadata(&path)
+        .await
+        .map_or(false, |meta| meta.is_dir())
+    {
+        return Err(anyhow!(
+            "no solution to {track}/{exercise} found in backup {}",
+            root.display()
+        ));
+    }
+
+    let metadata = read_solution_metadata(&path, cipher).await?;
+    let files = read_files(&path, cipher).await?;
+
+    let mut iterations = Vec::new();
+    for number in iteration_numbers(&path).await? {
+        let iteration_path = iterations_path(&path).join(number.to_string());
+        let files = read_files(&iteration_path, cipher).await?;
+        iterations.push(LocalIteration { number, files });
+    }
+
+    Ok(LocalSolution {
+        track: track.into(),
+        exercise: exercise.into(),
+        metadata,
+        files,
+        iterations,
+    })
+}
+
+/// Returns the path of the directory storing previous iterations of the solution at `solution_path`.
+pub fn iterations_path(solution_path: &Path) -> PathBuf {
+    solution_path.join(METADATA_DIR).join(ITERATIONS_DIR)
+}
+
+/// Returns the (sorted) numbers of the previous iterations stored for the solution at `solution_path`.
+pub async fn iteration_numbers(solution_path: &Path) -> Result<Vec<u32>> {
+    let iterations_path = iterations_path(solution_path);
+    if !fs::try_exists(&iterations_path).await.unwrap_or(false) {
+        return Ok(Vec::new());
+    }
+
+    let mut numbers = list_subdirectories(&iterations_path)
+        .await?
+        .into_iter()
+        .filter_map(|name| name.parse().ok())
+        .collect::<Vec<_>>();
+    numbers.sort_unstable();
+    Ok(numbers)
+}
+
+/// Reads all files of a solution (or iteration) directory, ignoring the [`METADATA_DIR`].
+async fn read_files(path: &Path, cipher: Option<&Cipher>) -> Result<Vec<LocalFile>> {
+    let mut files = Vec::new();
+    for relative_path in list_files(path).await? {
+        if relative_path.starts_with(METADATA_DIR) {
+            continue;
+        }
+        let content = read_file(&path.join(&relative_path), cipher).await?;
+        files.push(LocalFile { relative_path, content });
+    }
+
+    Ok(files)
+}
+
+/// Reads the metadata stored for the solution at `solution_path`, if any.
+pub async fn read_solution_metadata(
+    solution_path: &Path,
+    cipher: Option<&Cipher>,
+) -> Result<Option<Solution>> {
+    let metadata_path = solution_path
+        .join(METADATA_DIR)
+        .join(SOLUTION_METADATA_FILE);
+    if !fs::try_exists(&metadata_path).await.unwrap_or(false) {
+        return Ok(None);
+    }
+
+    let content = read_file(&metadata_path, cipher).await?;
+    serde_json::from_slice(&content)
+        .map(Some)
+        .with_context(|| format!("invalid solution metadata in {}", metadata_path.display()))
+}
+
+/// SHA-256 checksums of the files of a solution, indexed by path (using `/` as separator).
+pub type Checksums = BTreeMap<String, String>;
+
+/// Returns the path of the file storing checksums of the solution at `solution_path`.
+pub fn checksums_path(solution_path: &Path) -> PathBuf {
+    solution_path.join(METADATA_DIR).join(CHECKSUMS_FILE)
+}
+
+/// Reads the checksums stored for the solution at `solution_path`, if any.
+pub async fn read_checksums(solution_path: &Path) -> Result<Option<Checksums>> {
+    let checksums_path = checksums_path(solution_path);
+    if !fs::try_exists(&checksums_path).await.unwrap_or(false) {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&checksums_path)
+        .await
+        .with_context(|| format!("failed to read file {}", checksums_path.display()))?;
+    Ok(Some(parse_checksums(&content)))
+}
+
+/// Parses checksums in the format output by `sha256sum`; invalid lines are ignored.
+pub fn parse_checksums(content: &str) -> Checksums {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (checksum, path) = line.split_once(' ')?;
+            let path = path.strip_prefix([' ', '*']).unwrap_or(path);
+            Some((path.to_string(), checksum.to_ascii_lowercase()))
+        })
+        .collect()
+}
+
+/// Formats checksums in the format output by `sha256sum`.
+pub fn format_checksums(checksums: &Checksums) -> String {
+    checksums
+        .iter()
+        .fold(String::new(), |mut output, (path, checksum)| {
+            writeln!(output, "{checksum}  {path}").expect("writing to a String should not fail");
+            output
+        })
+}
+
+/// Returns the SHA-256 checksum of `data`, as a lowercase hexadecimal string.
+pub fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Returns the time at which the solution's latest iteration was submitted, falling back to the
+/// time the solution was last updated for solutions without iterations.
+pub fn submission_time(solution: &Solution) -> Option<SystemTime> {
+    solution
+        .last_iterated_at
+        .as_deref()
+        .unwrap_or(&solution.updated_at)
+        .parse::<DateTime<chrono::Utc>>()
+        .ok()
+        .map(SystemTime::from)
+}
+
+/// Sets the modification time of all files and directories of the solution at `solution_path`
+/// (including the solution directory itself), except those of previous iterations.
+pub async fn set_modification_times(solution_path: &Path, time: SystemTime) -> Result<()> {
+    let time = FileTime::from_system_time(time);
+    let iterations_path = iterations_path(Path::new(""));
+
+    let mut paths = list_files(solution_path)
+        .await?
+        .into_iter()
+        .filter(|path| !path.starts_with(&iterations_path))
+        .collect::<Vec<_>>();
+
+    // Directories are updated after their content, deepest first, since updating a directory's
+    // content could change its modification time.
+    let mut directories = paths
+        .iter()
+        .flat_map(|path| path.ancestors().skip(1))
+        .map(Path::to_path_buf)
+        .collect::<Vec<_>>();
+    directories.sort_by(|a, b| {
+        b.components()
+            .count()
+            .cmp(&a.components().count())
+            .then_with(|| a.cmp(b))
+    });
+    directories.dedup();
+    paths.extend(directories);
+
+    for path in paths {
+        let path = solution_path.join(path);
+        filetime::set_file_mtime(&path, time)
+            .with_context(|| format!("failed to set modification time of {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+/// Sets the modification time of the directory at `path` to the latest modification time of its
+/// subdirectories (ignoring hidden ones), if it has any.
+///
+/// This is used for directories grouping solutions (like tracks), once their content is written.
+pub async fn set_modification_time_from_subdirectories(path: &Path) -> Result<()> {
+    let mut latest = None;
+    for name in list_subdirectories(path).await? {
+        let subdirectory = path.join(name);
+        let metadata = fs::metadata(&subdirectory).await.with_context(|| {
+            format!("failed to get modification time of {}", subdirectory.display())
+        })?;
+        latest = latest.max(Some(FileTime::from_last_modification_time(&metadata)));
+    }
+
+    match latest {
+        Some(time) => filetime::set_file_mtime(path, time)
+            .with_context(|| format!("failed to set modification time of {}", path.display())),
+        None => Ok(()),
+    }
+}
+
+/// Reads a file from a backup, decrypting it if needed.
+pub async fn read_file(path: &Path, cipher: Option<&Cipher>) -> Result<Vec<u8>> {
+    let data = fs::read(path)
+        .await
+        .with_context(|| format!("failed to read file {}", path.display()))?;
+
+    match (Cipher::is_encrypted(&data), cipher) {
+        (false, _) => Ok(data),
+        (true, Some(cipher)) => cipher
+            .decrypt(&data)
+            .with_context(|| format!("failed to decrypt file {}", path.display())),
+        (true, None) => Err(anyhow!(
+            "file {} is encrypted; an encryption key file or passphrase is needed",
+            path.display()
+        )),
+    }
+}
+
+/// Lists all files under `root`, recursively.
+///
+/// Returned paths are relative to `root` and sorted.
+pub async fn list_files(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut directories = vec![PathBuf::new()];
+
+    while let Some(relative_directory) = directories.pop() {
+        let directory = root.join(&relative_directory);
+        let mut entries = fs::read_dir(&directory)
+            .await
+            .with_context(|| format!("failed to list directory {}", directory.display()))?;
+
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .with_context(|| format!("failed to list directory {}", directory.display()))?
+        {
+            let relative_path = relative_directory.join(entry.file_name());
+            let file_type = entry
+                .file_type()
+                .await
+                .with_context(|| format!("failed to get type of {}", entry.path().display()))?;
+            match file_type.is_dir() {
+                true => directories.push(relative_path),
+                false => files.push(relative_path),
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Lists the names of the subdirectories of `path`, ignoring hidden ones.
+pub async fn list_subdirectories(path: &Path) -> Result<Vec<String>> {
+    let mut subdirectories = Vec::new();
+    let mut entries = fs::read_dir(path)
+        .await
+        .with_context(|| format!("failed to list directory {}", path.display()))?;
+
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .with_context(|| format!("failed to list directory {}", path.display()))?
+    {
+        let is_dir = entry
+            .file_type()
+            .await
+            .with_context(|| format!("failed to get type of {}", entry.path().display()))?
+            .is_dir();
+        match entry.file_name().into_string() {
+            Ok(name) if is_dir && !name.starts_with('.') => subdirectories.push(name),
+            _ => (),
+        }
+    }
+
+    subdirectories.sort();
+    Ok(subdirectories)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let file =
+            |content: &[u8]| LocalFile { relative_path: "file".into(), content: content.into() };
+
+        assert_eq!(Some(0), file(b"").lines());
+        assert_eq!(Some(1), file(b"fn main() {}").lines());
+        assert_eq!(Some(2), file(b"line 1\nline 2\n").lines());
+        assert_eq!(None, file(&[0xff, 0xfe, 0x00]).lines());
+    }
+
+    #[test]
+    fn test_checksums() {
+        let checksums = Checksums::from([
+            ("src/lib.rs".to_string(), sha256_hex(b"fn main() {}")),
+            ("Cargo.toml".to_string(), sha256_hex(b"")),
+        ]);
+
+        let formatted = format_checksums(&checksums);
+        assert_eq!(
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855  Cargo.toml\n",
+            formatted.lines().next().unwrap().to_string() + "\n"
+        );
+        assert_eq!(checksums, parse_checksums(&formatted));
+        assert_eq!(
+            Checksums::from([("a b.txt".to_string(), "abcd".to_string())]),
+            parse_checksums("ABCD *a b.txt\ninvalid\n")
+        );
+    }
+}
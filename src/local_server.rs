@@ -0,0 +1,94 @@
+//! HTTP servers listening on a local port, used to stand in for the Exercism API.
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::{SocketAddr, TcpListener};
+
+use anyhow::Context;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server};
+use tokio::sync::oneshot;
+use tracing::warn;
+
+use crate::Result;
+
+/// HTTP server listening on a local port, passing every request to a handler.
+///
+/// The server is stopped when dropped.
+#[derive(Debug)]
+pub struct LocalServer {
+    address: SocketAddr,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl LocalServer {
+    /// Starts a server using the provided listener (or on a random local port if [`None`]).
+    ///
+    /// `name` describes the server in error messages (e.g. `"caching proxy"`).
+    pub fn start<H, F>(listener: Option<TcpListener>, name: &str, handler: H) -> Result<Self>
+    where
+        H: Fn(Request<Body>) -> F + Clone + Send + 'static,
+        F: Future<Output = Response<Body>> + Send + 'static,
+    {
+        let listener = match listener {
+            Some(listener) => listener,
+            None => TcpListener::bind("127.0.0.1:0")
+                .with_context(|| format!("failed to bind {name}"))?,
+        };
+        listener
+            .set_nonblocking(true)
+            .with_context(|| format!("failed to bind {name}"))?;
+        let address = listener
+            .local_addr()
+            .with_context(|| format!("failed to get {name} address"))?;
+
+        let make_service = make_service_fn(move |_| {
+            let handler = handler.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let response = handler(request);
+                    async move { Ok::<_, Infallible>(response.await) }
+                }))
+            }
+        });
+        let (shutdown, shutdown_signal) = oneshot::channel();
+        let server = Server::from_tcp(listener)
+            .with_context(|| format!("failed to start {name}"))?
+            .serve(make_service)
+            .with_graceful_shutdown(async {
+                shutdown_signal.await.ok();
+            });
+        let name = name.to_string();
+        tokio::spawn(async move {
+            if let Err(error) = server.await {
+                warn!("Local {name} failed: {error}");
+            }
+        });
+
+        Ok(Self { address, shutdown: Some(shutdown) })
+    }
+
+    /// Returns the base URI of the server.
+    pub fn uri(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// Builds an HTTP client to send requests to local servers.
+    ///
+    /// Proxies are bypassed, since they could not reach a local server: proxy, CA certificate and
+    /// user agent settings only apply to the requests that the server forwards to Exercism.
+    pub fn http_client() -> Result<reqwest::Client> {
+        reqwest::Client::builder()
+            .no_proxy()
+            .build()
+            .with_context(|| "failed to create HTTP client")
+    }
+}
+
+impl Drop for LocalServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Full-text search in the files of a local backup.
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::local_backup::{LocalFile, LocalSolution};
+
+/// Options controlling a [`search`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Number of lines to include before and after each matching line.
+    pub context: usize,
+
+    /// Whether to also search previous iterations of solutions.
+    pub all_iterations: bool,
+}
+
+/// Matches found in a single backed-up file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileMatches {
+    pub track: String,
+    pub exercise: String,
+    /// Number of the previous iteration containing the file, or [`None`] for the latest one.
+    pub iteration: Option<u32>,
+    pub path: String,
+    pub lines: Vec<SearchLine>,
+}
+
+/// Line of a [`FileMatches`], either matching the search or included as context.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchLine {
+    pub number: usize,
+    pub text: String,
+    pub is_match: bool,
+}
+
+/// Returns matches for `regex` in the files of the given solutions.
+///
+/// Files are returned in the same order as solutions, files of the latest iteration coming first.
+/// Binary files are ignored.
+pub fn search(
+    solutions: &[LocalSolution],
+    regex: &Regex,
+    options: SearchOptions,
+) -> Vec<FileMatches> {
+    let mut matches = Vec::new();
+
+    for solution in solutions {
+        let iterations = solution
+            .iterations
+            .iter()
+            .rev()
+            .filter(|_| options.all_iterations)
+            .map(|iteration| (Some(iteration.number), &iteration.files));
+
+        for (iteration, files) in [(None, &solution.files)].into_iter().chain(iterations) {
+            matches.extend(files.iter().filter_map(|file| {
+                let lines = search_file(file, regex, options.context)?;
+                Some(FileMatches {
+                    track: solution.track.clone(),
+                    exercise: solution.exercise.clone(),
+                    iteration,
+                    path: file.display_path(),
+                    lines,
+                })
+            }));
+        }
+    }
+
+    matches
+}
+
+fn search_file(file: &LocalFile, regex: &Regex, context: usize) -> Option<Vec<SearchLine>> {
+    let text = std::str::from_utf8(&file.content).ok()?;
+    let lines = text.lines().collect::<Vec<_>>();
+
+    let matching = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| regex.is_match(line))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    if matching.is_empty() {
+        return None;
+    }
+
+    let mut included = vec![false; lines.len()];
+    for &i in &matching {
+        let end = (i + context).min(lines.len() - 1);
+        included[i.saturating_sub(context)..=end].fill(true);
+    }
+
+    Some(
+        lines
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| included[*i])
+            .map(|(i, line)| SearchLine {
+                number: i + 1,
+                text: line.to_string(),
+                is_match: matching.binary_search(&i).is_ok(),
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::local_backup::LocalIteration;
+
+    fn file(path: &str, content: &str) -> LocalFile {
+        LocalFile { relative_path: path.into(), content: content.as_bytes().to_vec() }
+    }
+
+    fn solution() -> LocalSolution {
+        LocalSolution {
+            track: "rust".into(),
+            exercise: "poker".into(),
+            metadata: None,
+            files: vec![
+                file("src/lib.rs", "use std::cmp;\n\nfn winning_hands() {\n    todo!()\n}\n"),
+                LocalFile { relative_path: "data.bin".into(), content: vec![0xff, b'f', b'n'] },
+            ],
+            iterations: vec![LocalIteration {
+                number: 1,
+                files: vec![file("src/lib.rs", "fn main() {}\n")],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_search() {
+        let matches =
+            search(&[solution()], &Regex::new(r"\bfn\b").unwrap(), SearchOptions::default());
+
+        assert_eq!(
+            vec![FileMatches {
+                track: "rust".into(),
+                exercise: "poker".into(),
+                iteration: None,
+                path: "src/lib.rs".into(),
+                lines: vec![SearchLine {
+                    number: 3,
+                    text: "fn winning_hands() {".into(),
+                    is_match: true,
+                }],
+            }],
+            matches
+        );
+    }
+
+    #[test]
+    fn test_search_with_context() {
+        let options = SearchOptions { context: 1, all_iterations: false };
+        let matches = search(&[solution()], &Regex::new("todo").unwrap(), options);
+
+        assert_eq!(
+            vec![(3, false), (4, true), (5, false)],
+            matches[0]
+                .lines
+                .iter()
+                .map(|line| (line.number, line.is_match))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_search_all_iterations() {
+        let options = SearchOptions { context: 0, all_iterations: true };
+        let matches = search(&[solution()], &Regex::new(r"\bfn\b").unwrap(), options);
+
+        assert_eq!(
+            vec![None, Some(1)],
+            matches
+                .iter()
+                .map(|file_matches| file_matches.iteration)
+                .collect::<Vec<_>>()
+        );
+    }
+}
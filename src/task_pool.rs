@@ -1,28 +1,202 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
 use std::fmt::Display;
 use std::future::Future;
 use std::panic::resume_unwind;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
 
-use anyhow::anyhow;
-use tokio::task::JoinSet;
+use anyhow::{anyhow, Context};
+use tokio::sync::{mpsc, oneshot, OwnedSemaphorePermit, Semaphore};
+use tokio::task::{JoinError, JoinSet};
+use tracing::debug;
 
-use crate::error::MultiError;
+use crate::error::{Error, MultiError};
 use crate::Result;
 
+/// Task as run by the pool: returns [`None`] for subtasks, whose result is sent to their parent.
+type BoxedTask = Pin<Box<dyn Future<Output = Option<Result<()>>> + Send + 'static>>;
+
+/// Slot of a [bounded](TaskPool::bounded) pool held by a running task, if any.
+type Slot = Arc<Mutex<Option<OwnedSemaphorePermit>>>;
+
+/// Priority of a task spawned in a [bounded](TaskPool::bounded) [`TaskPool`].
+///
+/// Queued tasks with a higher priority are started first; tasks with the same priority are
+/// started in the order they were spawned.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TaskPriority {
+    #[default]
+    Normal,
+    High,
+}
+
+/// Policy determining when a [`TaskPool`] gives up after tasks fail.
+///
+/// When the policy's limit is exceeded, running tasks are aborted and queued tasks are dropped.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum FailurePolicy {
+    /// Wait for all tasks, whatever the number of failures.
+    #[default]
+    Continue,
+
+    /// Abort remaining tasks as soon as one task fails.
+    FailFast,
+
+    /// Abort remaining tasks once more than this number of tasks failed.
+    MaxFailures(usize),
+
+    /// Abort remaining tasks once more than this percentage of completed tasks failed.
+    ///
+    /// The rate is only checked once [`MIN_FAILURE_RATE_SAMPLE`] tasks completed, so that a
+    /// failure among the first tasks doesn't abort all others.
+    MaxFailureRate(u32),
+}
+
+/// Number of tasks that must complete before a [`FailurePolicy::MaxFailureRate`] is checked.
+pub const MIN_FAILURE_RATE_SAMPLE: usize = 10;
+
+impl FailurePolicy {
+    fn exceeded(&self, failures: usize, completed: usize) -> bool {
+        match *self {
+            Self::Continue => false,
+            Self::FailFast => failures > 0,
+            Self::MaxFailures(max_failures) => failures > max_failures,
+            Self::MaxFailureRate(percent) => {
+                completed >= MIN_FAILURE_RATE_SAMPLE
+                    && failures * 100 > completed * percent as usize
+            },
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct TaskPool {
-    join_set: JoinSet<Result<()>>,
+    join_set: JoinSet<(Slot, Option<Result<()>>)>,
+    slots: Option<Arc<Semaphore>>,
+    queue: BinaryHeap<QueuedTask>,
+    next_sequence: u64,
+    subtask_sender: mpsc::UnboundedSender<QueuedTask>,
+    subtask_receiver: mpsc::UnboundedReceiver<QueuedTask>,
+    errors: Vec<Error>,
+    failure_policy: FailurePolicy,
+    failures: usize,
+    completed: usize,
+    aborted: bool,
 }
 
 impl TaskPool {
     pub fn new() -> Self {
-        Self { join_set: JoinSet::new() }
+        let (subtask_sender, subtask_receiver) = mpsc::unbounded_channel();
+        Self {
+            join_set: JoinSet::new(),
+            slots: None,
+            queue: BinaryHeap::new(),
+            next_sequence: 0,
+            subtask_sender,
+            subtask_receiver,
+            errors: Vec::new(),
+            failure_policy: FailurePolicy::default(),
+            failures: 0,
+            completed: 0,
+            aborted: false,
+        }
+    }
+
+    /// Creates a pool running at most `max_running` tasks at once.
+    ///
+    /// Other tasks are queued and only started when running tasks complete, so that they don't
+    /// use resources (like connections or buffers) until then. Tasks waiting for their
+    /// [subtasks](TaskSpawner::join) don't count towards that bound.
+    pub fn bounded(max_running: usize) -> Self {
+        Self { slots: Some(Arc::new(Semaphore::new(max_running.max(1)))), ..Self::new() }
+    }
+
+    /// Sets the policy used to determine when to abort remaining tasks after failures.
+    pub fn with_failure_policy(mut self, failure_policy: FailurePolicy) -> Self {
+        self.failure_policy = failure_policy;
+        self
+    }
+
+    /// Returns `true` if remaining tasks were aborted because of the [`FailurePolicy`].
+    ///
+    /// Tasks spawned after that are ignored.
+    pub fn is_aborted(&self) -> bool {
+        self.aborted
     }
 
     pub fn spawn<F>(&mut self, task: F)
     where
         F: Future<Output = Result<()>> + Send + 'static,
     {
-        self.join_set.spawn(task);
+        self.spawn_with_priority(TaskPriority::default(), task);
+    }
+
+    /// Spawns a task, queueing it with the given `priority` if the pool is full.
+    pub fn spawn_with_priority<F>(&mut self, priority: TaskPriority, task: F)
+    where
+        F: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.spawn_with_subtasks(priority, |_| task);
+    }
+
+    /// Spawns a task that can run subtasks in the pool using the [`TaskSpawner`] passed to `task`,
+    /// queueing it with the given `priority` if the pool is full.
+    pub fn spawn_with_subtasks<T, F>(&mut self, priority: TaskPriority, task: T)
+    where
+        T: FnOnce(TaskSpawner) -> F,
+        F: Future<Output = Result<()>> + Send + 'static,
+    {
+        if self.aborted {
+            return;
+        }
+
+        let slot = Slot::default();
+        let spawner = TaskSpawner {
+            sender: self.subtask_sender.clone(),
+            slot: slot.clone(),
+            subtasks: Vec::new(),
+        };
+        let task = task(spawner);
+        self.enqueue(QueuedTask::new(priority, slot, Box::pin(async move { Some(task.await) })));
+        self.start_queued();
+    }
+
+    /// Waits until a task can be spawned without being queued.
+    ///
+    /// This can be used to apply backpressure when tasks are produced faster than they complete.
+    pub async fn ready(&mut self) {
+        self.receive_subtasks();
+        while !self.aborted && (self.is_full() || !self.queue.is_empty()) {
+            if !self.join_next().await {
+                break;
+            }
+        }
+    }
+
+    /// Awaits `future`, starting queued tasks and subtasks in the meantime.
+    ///
+    /// Since subtasks are only started while the pool is awaited, this should be used instead of
+    /// awaiting other futures directly while tasks with subtasks are running.
+    pub async fn run_until<F: Future>(&mut self, future: F) -> F::Output {
+        tokio::pin!(future);
+        loop {
+            tokio::select! {
+                biased;
+                output = &mut future => return output,
+                running = self.join_next() => {
+                    if !running {
+                        return future.await;
+                    }
+                },
+            }
+        }
+    }
+
+    /// Records an error that occurred outside of the pool's tasks, to be reported by
+    /// [`join`](Self::join). It does not count towards the [`FailurePolicy`].
+    pub fn add_error(&mut self, error: Error) {
+        self.errors.push(error);
     }
 
     pub async fn join<C, F>(&mut self, context: F) -> Result<()>
@@ -30,26 +204,227 @@ impl TaskPool {
         F: FnOnce() -> C,
         C: Display + Send + Sync + 'static,
     {
-        let mut errors = Vec::new();
+        while self.join_next().await {}
+
+        let result = MultiError::check(std::mem::take(&mut self.errors), context);
+        match self.aborted {
+            true => result.with_context(|| "remaining tasks were cancelled because of failures"),
+            false => result,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.slots
+            .as_ref()
+            .map_or(false, |slots| slots.available_permits() == 0)
+    }
+
+    fn enqueue(&mut self, mut queued: QueuedTask) {
+        queued.sequence = self.next_sequence;
+        self.next_sequence += 1;
+        self.queue.push(queued);
+    }
+
+    fn receive_subtasks(&mut self) {
+        while let Ok(subtask) = self.subtask_receiver.try_recv() {
+            if !self.aborted {
+                self.enqueue(subtask);
+            }
+        }
+    }
+
+    /// Starts queued tasks, by priority, while slots are available.
+    fn start_queued(&mut self) {
+        while !self.queue.is_empty() {
+            let permit = match &self.slots {
+                Some(slots) => match slots.clone().try_acquire_owned() {
+                    Ok(permit) => Some(permit),
+                    Err(_) => break,
+                },
+                None => None,
+            };
+            let queued = self.queue.pop().expect("queue should not be empty");
+            self.start(queued, permit);
+        }
+    }
 
-        while let Some(join_result) = self.join_set.join_next().await {
-            match join_result {
-                Ok(Ok(_)) => (),
-                Ok(Err(task_error)) => errors.push(task_error),
-                Err(join_error) => match join_error.try_into_panic() {
-                    Ok(panic_err) => resume_unwind(panic_err),
-                    Err(join_error) => errors.push(anyhow!("Join error: {join_error}")),
+    fn start(&mut self, queued: QueuedTask, permit: Option<OwnedSemaphorePermit>) {
+        let QueuedTask { slot, task, .. } = queued;
+        *slot.lock().expect("slot lock should not be poisoned") = permit;
+        // The slot is returned, so that it is only released once the task is recorded.
+        self.join_set.spawn(async move { (slot, task.await) });
+    }
+
+    /// Waits for a running task to complete, starting queued tasks and subtasks in the meantime.
+    ///
+    /// Returns `false` if no task was running.
+    async fn join_next(&mut self) -> bool {
+        loop {
+            self.receive_subtasks();
+            self.start_queued();
+            if self.join_set.is_empty() {
+                return false;
+            }
+
+            // Slots can also be released by tasks waiting for their subtasks.
+            let slots = self.slots.clone().filter(|_| !self.queue.is_empty());
+            tokio::select! {
+                Some(join_result) = self.join_set.join_next() => {
+                    self.record(join_result.map(|(_slot, result)| result));
+                    self.receive_subtasks();
+                    self.start_queued();
+                    return true;
+                },
+                Some(subtask) = self.subtask_receiver.recv() => {
+                    if !self.aborted {
+                        self.enqueue(subtask);
+                    }
+                },
+                Ok(permit) = async { slots.expect("slots should exist").acquire_owned().await }, if slots.is_some() => {
+                    match self.queue.pop() {
+                        Some(queued) => self.start(queued, Some(permit)),
+                        None => drop(permit),
+                    }
                 },
             }
         }
+    }
+
+    fn record(&mut self, join_result: std::result::Result<Option<Result<()>>, JoinError>) {
+        let error = match join_result {
+            // Subtasks report their result to the task that spawned them.
+            Ok(None) => return,
+            Ok(Some(Ok(_))) => None,
+            Ok(Some(Err(task_error))) => Some(task_error),
+            Err(join_error) if join_error.is_cancelled() && self.aborted => return,
+            Err(join_error) => match join_error.try_into_panic() {
+                Ok(panic_err) => resume_unwind(panic_err),
+                Err(join_error) => Some(anyhow!("Join error: {join_error}")),
+            },
+        };
+        self.completed += 1;
+        if let Some(error) = error {
+            self.failures += 1;
+            self.errors.push(error);
+        }
+
+        if !self.aborted && self.failure_policy.exceeded(self.failures, self.completed) {
+            debug!("Aborting remaining tasks after {} failure(s)", self.failures);
+            self.aborted = true;
+            self.queue.clear();
+            self.join_set.abort_all();
+        }
+    }
+}
+
+/// Handle used by a task of a [`TaskPool`] to run subtasks in the same pool (see
+/// [`TaskPool::spawn_with_subtasks`]).
+///
+/// Subtasks are part of the task that spawned them: they don't count towards the pool's
+/// [`FailurePolicy`], their errors are returned by [`join`](Self::join) instead.
+#[derive(Debug)]
+pub struct TaskSpawner {
+    sender: mpsc::UnboundedSender<QueuedTask>,
+    slot: Slot,
+    subtasks: Vec<oneshot::Receiver<Result<()>>>,
+}
+
+impl TaskSpawner {
+    /// Spawns a subtask, queueing it with the given `priority` if the pool is full.
+    pub fn spawn_with_priority<F>(&mut self, priority: TaskPriority, task: F)
+    where
+        F: Future<Output = Result<()>> + Send + 'static,
+    {
+        let (result_sender, result_receiver) = oneshot::channel();
+        let task = Box::pin(async move {
+            result_sender.send(task.await).ok();
+            None
+        });
+
+        // If the pool was dropped, so is the subtask; `join` then reports it as cancelled.
+        self.sender
+            .send(QueuedTask::new(priority, Slot::default(), task))
+            .ok();
+        self.subtasks.push(result_receiver);
+    }
+
+    /// Waits for the subtasks spawned so far, returning their errors.
+    ///
+    /// The task's slot is released while waiting, so that its subtasks can start even if the
+    /// pool is full; the task then runs without a slot until it completes.
+    pub async fn join<C, F>(&mut self, context: F) -> Result<()>
+    where
+        F: FnOnce() -> C,
+        C: Display + Send + Sync + 'static,
+    {
+        self.slot
+            .lock()
+            .expect("slot lock should not be poisoned")
+            .take();
+
+        let mut errors = Vec::new();
+        for subtask in self.subtasks.drain(..) {
+            match subtask.await {
+                Ok(Ok(())) => (),
+                Ok(Err(error)) => errors.push(error),
+                Err(_) => errors.push(anyhow!("task was cancelled")),
+            }
+        }
 
         MultiError::check(errors, context)
     }
 }
 
+struct QueuedTask {
+    priority: TaskPriority,
+    sequence: u64,
+    slot: Slot,
+    task: BoxedTask,
+}
+
+impl QueuedTask {
+    fn new(priority: TaskPriority, slot: Slot, task: BoxedTask) -> Self {
+        Self { priority, sequence: 0, slot, task }
+    }
+}
+
+impl std::fmt::Debug for QueuedTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueuedTask")
+            .field("priority", &self.priority)
+            .field("sequence", &self.sequence)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for QueuedTask {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueuedTask {}
+
+impl PartialOrd for QueuedTask {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedTask {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Highest priority first, then first spawned first (`BinaryHeap` is a max-heap).
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
 //noinspection DuplicatedCode
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering::SeqCst;
     use std::time::Duration;
 
     use anyhow::Context;
@@ -152,4 +527,184 @@ mod tests {
 
         assert!(task_pool.join(|| "error occurred").await.is_err());
     }
+
+    #[tokio::test]
+    async fn test_bounded() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_seen = Arc::new(AtomicUsize::new(0));
+        let mut task_pool = TaskPool::bounded(2);
+
+        for _ in 0..10 {
+            let running = running.clone();
+            let max_seen = max_seen.clone();
+            task_pool.ready().await;
+            task_pool.spawn(async move {
+                let now_running = running.fetch_add(1, SeqCst) + 1;
+                max_seen.fetch_max(now_running, SeqCst);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                running.fetch_sub(1, SeqCst);
+                Ok(())
+            });
+            assert!(task_pool.join_set.len() <= 2);
+        }
+
+        assert!(task_pool.join(|| "should not happen").await.is_ok());
+        assert_eq!(2, max_seen.load(SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_priorities() {
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut task_pool = TaskPool::bounded(1);
+
+        let task = |name: &'static str| {
+            let order = order.clone();
+            async move {
+                order.lock().unwrap().push(name);
+                Ok(())
+            }
+        };
+        task_pool.spawn(task("first"));
+        task_pool.spawn(task("normal 1"));
+        task_pool.spawn_with_priority(TaskPriority::High, task("high"));
+        task_pool.spawn(task("normal 2"));
+
+        assert!(task_pool.join(|| "should not happen").await.is_ok());
+        assert_eq!(vec!["first", "high", "normal 1", "normal 2"], *order.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_subtasks() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_seen = Arc::new(AtomicUsize::new(0));
+        let mut task_pool = TaskPool::bounded(2);
+
+        // Parents wait for their subtasks without holding a slot, so they can't starve them.
+        for parent in 0..4 {
+            let running = running.clone();
+            let max_seen = max_seen.clone();
+            task_pool.spawn_with_subtasks(TaskPriority::High, move |mut spawner| async move {
+                for subtask in 0..3 {
+                    let running = running.clone();
+                    let max_seen = max_seen.clone();
+                    spawner.spawn_with_priority(TaskPriority::Normal, async move {
+                        let now_running = running.fetch_add(1, SeqCst) + 1;
+                        max_seen.fetch_max(now_running, SeqCst);
+                        tokio::time::sleep(Duration::from_millis(5)).await;
+                        running.fetch_sub(1, SeqCst);
+                        match parent % 2 == 1 && subtask > 0 {
+                            true => Err(anyhow!("subtask {parent}/{subtask} failed")),
+                            false => Ok(()),
+                        }
+                    });
+                }
+                spawner.join(|| format!("parent {parent} failed")).await
+            });
+        }
+
+        let error = format!("{:#}", task_pool.join(|| "error occurred").await.unwrap_err());
+        assert!(error.contains("subtask 3/2 failed"));
+        assert!(max_seen.load(SeqCst) <= 2);
+        assert_eq!(4, task_pool.completed);
+        assert_eq!(2, task_pool.failures);
+    }
+
+    #[test]
+    fn test_failure_policy() {
+        assert!(!FailurePolicy::Continue.exceeded(10, 10));
+        assert!(!FailurePolicy::FailFast.exceeded(0, 10));
+        assert!(FailurePolicy::FailFast.exceeded(1, 10));
+        assert!(!FailurePolicy::MaxFailures(2).exceeded(2, 10));
+        assert!(FailurePolicy::MaxFailures(2).exceeded(3, 10));
+        assert!(!FailurePolicy::MaxFailureRate(10).exceeded(1, 10));
+        assert!(FailurePolicy::MaxFailureRate(10).exceeded(2, 10));
+        assert!(!FailurePolicy::MaxFailureRate(10).exceeded(1, 1));
+        assert!(!FailurePolicy::MaxFailureRate(10).exceeded(5, MIN_FAILURE_RATE_SAMPLE - 1));
+    }
+
+    #[tokio::test]
+    async fn test_max_failure_rate() {
+        let completed = Arc::new(AtomicUsize::new(0));
+        let mut task_pool =
+            TaskPool::bounded(1).with_failure_policy(FailurePolicy::MaxFailureRate(10));
+
+        // An early failure doesn't abort other tasks, since it is only 5% of all tasks.
+        task_pool.spawn(async { Err(anyhow!("first task failed")) });
+        for _ in 0..19 {
+            let completed = completed.clone();
+            task_pool.spawn(async move {
+                completed.fetch_add(1, SeqCst);
+                Ok(())
+            });
+        }
+
+        assert!(task_pool.join(|| "error occurred").await.is_err());
+        assert!(!task_pool.is_aborted());
+        assert_eq!(19, completed.load(SeqCst));
+
+        let mut task_pool =
+            TaskPool::bounded(1).with_failure_policy(FailurePolicy::MaxFailureRate(10));
+        for i in 0..20 {
+            task_pool.spawn(async move { Err(anyhow!("task {i} failed")) });
+        }
+
+        let error = format!("{:#}", task_pool.join(|| "error occurred").await.unwrap_err());
+        assert!(task_pool.is_aborted());
+        assert!(error.contains(&format!("task {} failed", MIN_FAILURE_RATE_SAMPLE - 1)));
+        assert!(!error.contains(&format!("task {} failed", MIN_FAILURE_RATE_SAMPLE)));
+    }
+
+    #[tokio::test]
+    async fn test_fail_fast() {
+        let completed = Arc::new(AtomicUsize::new(0));
+        let mut task_pool = TaskPool::bounded(2).with_failure_policy(FailurePolicy::FailFast);
+
+        task_pool.spawn(async { Err(anyhow!("first task failed")) });
+        for _ in 0..10 {
+            let completed = completed.clone();
+            task_pool.spawn(async move {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                completed.fetch_add(1, SeqCst);
+                Ok(())
+            });
+        }
+
+        let error = task_pool.join(|| "error occurred").await.unwrap_err();
+        assert!(task_pool.is_aborted());
+        assert!(format!("{error:#}").contains("first task failed"));
+        assert_eq!(0, completed.load(SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_max_failures() {
+        let mut task_pool = TaskPool::bounded(1).with_failure_policy(FailurePolicy::MaxFailures(1));
+
+        for i in 0..6 {
+            task_pool.spawn(async move { Err(anyhow!("task {i} failed")) });
+        }
+
+        let error = format!("{:#}", task_pool.join(|| "error occurred").await.unwrap_err());
+        assert!(task_pool.is_aborted());
+        assert!(error.contains("task 1 failed"));
+        assert!(!error.contains("task 2 failed"));
+    }
+
+    #[tokio::test]
+    async fn test_bounded_errors() {
+        let mut task_pool = TaskPool::bounded(1);
+
+        for i in 0..4 {
+            task_pool.spawn(async move {
+                match i % 2 {
+                    0 => Ok(()),
+                    _ => Err(anyhow!("task {i} failed")),
+                }
+            });
+        }
+
+        let error = task_pool.join(|| "error occurred").await.unwrap_err();
+        let error = format!("{:#}", error);
+        assert!(error.contains("task 1 failed"));
+        assert!(error.contains("task 3 failed"));
+    }
 }
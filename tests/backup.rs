@@ -0,0 +1,464 @@
+mod common;
+
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use chrono::{DateTime, Utc};
+use common::{exsb_command, fixtures_path, TestEnv};
+use sha2::{Digest, Sha256};
+
+fn fixture_file(account: &str, track: &str, exercise: &str, file: &str) -> PathBuf {
+    fixtures_path()
+        .join(account)
+        .join("solutions")
+        .join(track)
+        .join(exercise)
+        .join("files")
+        .join(file)
+}
+
+fn time(time: &str) -> SystemTime {
+    SystemTime::from(time.parse::<DateTime<Utc>>().unwrap())
+}
+
+fn backup_command(env: &TestEnv, output: &TempDir) -> Command {
+    let mut cmd = env.command("backup");
+    cmd.arg(output.path());
+    cmd
+}
+
+#[tokio::test]
+async fn test_backup() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+
+    backup_command(&env, &output)
+        .arg("--token")
+        .arg("alice-token")
+        .assert()
+        .success();
+
+    for (track, exercise, file) in [
+        ("rust", "hello-world", "src/lib.rs"),
+        ("rust", "hello-world", "Cargo.toml"),
+        ("rust", "poker", "src/lib.rs"),
+        ("elixir", "two-fer", "lib/two_fer.ex"),
+    ] {
+        output
+            .child(track)
+            .child(exercise)
+            .child(file)
+            .assert(std::fs::read_to_string(fixture_file("alice", track, exercise, file)).unwrap());
+    }
+    output.child("python").assert(predicates::path::missing());
+}
+
+#[tokio::test]
+async fn test_backup_with_filters() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+
+    backup_command(&env, &output)
+        .args(["--token", "alice-token", "--track", "rust", "--status", "published"])
+        .assert()
+        .success();
+
+    output
+        .child("rust/hello-world/src/lib.rs")
+        .assert(predicates::path::exists());
+    output
+        .child("rust/poker")
+        .assert(predicates::path::missing());
+    output.child("elixir").assert(predicates::path::missing());
+}
+
+#[tokio::test]
+async fn test_backup_with_property_filters() {
+    let env = TestEnv::start().await;
+
+    for (args, expected) in [
+        (&["--mentoring-status", "in-progress,finished"][..], &["rust/poker"][..]),
+        (&["--unpublished", "--min-iterations", "2"], &["rust/poker"]),
+        (&["--exercise-type", "practice"], &["rust/poker", "elixir/two-fer"]),
+        (&["--min-stars", "1"], &[]),
+    ] {
+        let output = TempDir::new().unwrap();
+        backup_command(&env, &output)
+            .args(["--token", "alice-token"])
+            .args(args)
+            .assert()
+            .success();
+
+        for solution in ["rust/hello-world", "rust/poker", "elixir/two-fer"] {
+            let child = output.child(solution);
+            match expected.contains(&solution) {
+                true => child.assert(predicates::path::exists()),
+                false => child.assert(predicates::path::missing()),
+            };
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_backup_multiple_pages() {
+    let fixtures = TempDir::new().unwrap();
+    let account = fixtures.child("carol");
+    account.child("token").write_str("carol-token").unwrap();
+    let num_solutions = exsb::fake_server::DEFAULT_PER_PAGE * 2 + 5;
+    for i in 0..num_solutions {
+        account
+            .child(format!("solutions/rust/exercise-{i:03}/files/src/lib.rs"))
+            .write_str("pub fn answer() -> i32 { 42 }\n")
+            .unwrap();
+    }
+
+    let env = TestEnv::start_with_fixtures(fixtures.path()).await;
+    let output = TempDir::new().unwrap();
+
+    backup_command(&env, &output)
+        .args(["--token", "carol-token", "--max-downloads", "2"])
+        .assert()
+        .success();
+
+    for i in 0..num_solutions {
+        output
+            .child(format!("rust/exercise-{i:03}/src/lib.rs"))
+            .assert(predicates::path::exists());
+    }
+}
+
+#[tokio::test]
+async fn test_backup_with_invalid_token() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+
+    backup_command(&env, &output)
+        .arg("--token")
+        .arg("invalid-token")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Exercism API token from --token option was rejected"));
+}
+
+#[tokio::test]
+async fn test_backup_multiple_accounts() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+    let config = TempDir::new().unwrap();
+    let accounts_file = config.child("accounts.txt");
+    accounts_file
+        .write_str("alice = alice-token\nbob = bob-token\n")
+        .unwrap();
+
+    backup_command(&env, &output)
+        .arg("--accounts-file")
+        .arg(accounts_file.path())
+        .assert()
+        .success();
+
+    output
+        .child("alice/rust/hello-world/src/lib.rs")
+        .assert(predicates::path::exists());
+    output
+        .child("bob/python/leap/leap.py")
+        .assert(predicates::path::exists());
+}
+
+#[tokio::test]
+async fn test_backup_error_report() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+    let config = TempDir::new().unwrap();
+    let accounts_file = config.child("accounts.txt");
+    let error_report = config.child("errors.json");
+
+    accounts_file
+        .write_str("alice = alice-token\nmallory = invalid-token\n")
+        .unwrap();
+    backup_command(&env, &output)
+        .arg("--accounts-file")
+        .arg(accounts_file.path())
+        .arg("--error-report")
+        .arg(error_report.path())
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("1 account failed: "));
+
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(error_report.path()).unwrap()).unwrap();
+    assert_eq!("account", report["children"][0]["kind"]);
+    assert_eq!("mallory", report["children"][0]["name"]);
+    assert_eq!(1, report["summary"][0]["count"]);
+    output
+        .child("alice/rust/hello-world/src/lib.rs")
+        .assert(predicates::path::exists());
+
+    accounts_file.write_str("alice = alice-token\n").unwrap();
+    backup_command(&env, &output)
+        .arg("--accounts-file")
+        .arg(accounts_file.path())
+        .arg("--error-report")
+        .arg(error_report.path())
+        .assert()
+        .success();
+
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(error_report.path()).unwrap()).unwrap();
+    assert_eq!(serde_json::json!({ "summary": [] }), report);
+}
+
+#[tokio::test]
+async fn test_encrypted_backup_and_restore() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+    let restored = TempDir::new().unwrap();
+    let original = std::fs::read(fixture_file("bob", "python", "leap", "leap.py")).unwrap();
+
+    backup_command(&env, &output)
+        .args(["--token", "bob-token"])
+        .env("EXSB_ENCRYPTION_PASSPHRASE", "hunter2")
+        .assert()
+        .success();
+
+    let encrypted = std::fs::read(output.child("python/leap/leap.py").path()).unwrap();
+    assert_ne!(original, encrypted);
+
+    env.exsb()
+        .arg("restore")
+        .arg(output.path())
+        .arg(restored.path())
+        .env("EXSB_ENCRYPTION_PASSPHRASE", "hunter2")
+        .assert()
+        .success();
+
+    let decrypted = std::fs::read(restored.child("python/leap/leap.py").path()).unwrap();
+    assert_eq!(original, decrypted);
+    restored
+        .child("python/leap/.exsb/SHA256SUMS")
+        .assert(predicates::path::missing());
+}
+
+#[tokio::test]
+async fn test_backup_skips_existing_solutions() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+    let file_downloads = || {
+        env.server
+            .received_requests()
+            .into_iter()
+            .filter(|request| request.url.path().contains("/files/"))
+            .count()
+    };
+
+    backup_command(&env, &output)
+        .args(["--token", "alice-token"])
+        .assert()
+        .success();
+    let downloads_after_first_backup = file_downloads();
+    assert!(downloads_after_first_backup > 0);
+
+    backup_command(&env, &output)
+        .args(["--token", "alice-token"])
+        .assert()
+        .success();
+    assert_eq!(downloads_after_first_backup, file_downloads());
+
+    backup_command(&env, &output)
+        .args(["--token", "alice-token", "--force"])
+        .assert()
+        .success();
+    assert_eq!(downloads_after_first_backup * 2, file_downloads());
+}
+
+#[tokio::test]
+async fn test_backup_verify_existing() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+    let original = std::fs::read(fixture_file("alice", "rust", "poker", "src/lib.rs")).unwrap();
+
+    backup_command(&env, &output)
+        .args(["--token", "alice-token"])
+        .assert()
+        .success();
+
+    let checksum = format!("{:x}", Sha256::digest(&original));
+    output
+        .child("rust/poker/.exsb/SHA256SUMS")
+        .assert(predicates::str::contains(format!("{checksum}  src/lib.rs\n")));
+
+    let lib_rs = output.child("rust/poker/src/lib.rs");
+    lib_rs.write_str("corrupted").unwrap();
+    backup_command(&env, &output)
+        .args(["--token", "alice-token"])
+        .assert()
+        .success();
+    lib_rs.assert("corrupted");
+
+    backup_command(&env, &output)
+        .args(["--token", "alice-token", "--verify-existing"])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("Corrupt or missing file(s) in solution to rust/poker: src/lib.rs"));
+    assert_eq!(original, std::fs::read(lib_rs.path()).unwrap());
+}
+
+#[tokio::test]
+async fn test_backup_preserves_submission_times() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+    let submitted_at = time("2024-02-15T08:30:00Z");
+    let modified = |path: &str| {
+        std::fs::metadata(output.child(path).path())
+            .unwrap()
+            .modified()
+            .unwrap()
+    };
+
+    for args in [&["--token", "alice-token"][..], &["--token", "alice-token", "--force"]] {
+        backup_command(&env, &output).args(args).assert().success();
+
+        for path in [
+            "rust/poker",
+            "rust/poker/src",
+            "rust/poker/src/lib.rs",
+            "rust/poker/.exsb",
+            "rust/poker/.exsb/solution.json",
+        ] {
+            assert_eq!(submitted_at, modified(path), "modification time of {path}");
+        }
+        // Tracks get the time of their latest solution.
+        assert_eq!(time("2024-03-01T12:00:00Z"), modified("rust"));
+        assert_eq!(time("2024-01-10T18:45:00Z"), modified("elixir"));
+    }
+}
+
+#[tokio::test]
+async fn test_backup_multiple_accounts_preserves_submission_times() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+    let config = TempDir::new().unwrap();
+    let accounts_file = config.child("accounts.txt");
+    accounts_file.write_str("alice = alice-token\n").unwrap();
+
+    backup_command(&env, &output)
+        .arg("--accounts-file")
+        .arg(accounts_file.path())
+        .assert()
+        .success();
+
+    let modified = std::fs::metadata(output.child("alice").path())
+        .unwrap()
+        .modified()
+        .unwrap();
+    assert_eq!(time("2024-03-01T12:00:00Z"), modified);
+}
+
+#[tokio::test]
+async fn test_record_and_replay() {
+    let env = TestEnv::start().await;
+    let recorded = TempDir::new().unwrap();
+    let replayed = TempDir::new().unwrap();
+    let session = TempDir::new().unwrap();
+
+    backup_command(&env, &recorded)
+        .args(["--token", "bob-token", "--record"])
+        .arg(session.path())
+        .assert()
+        .success();
+    let TestEnv { server, cache_dir } = env;
+    drop(server);
+
+    for entry in std::fs::read_dir(session.path()).unwrap() {
+        let content = std::fs::read(entry.unwrap().path()).unwrap();
+        assert!(!String::from_utf8_lossy(&content).contains("bob-token"));
+    }
+
+    exsb_command(&cache_dir)
+        .arg("backup")
+        .arg(replayed.path())
+        .arg("--replay")
+        .arg(session.path())
+        .assert()
+        .success();
+
+    replayed
+        .child("python/leap/leap.py")
+        .assert(std::fs::read_to_string(fixture_file("bob", "python", "leap", "leap.py")).unwrap());
+}
+
+#[tokio::test]
+async fn test_export_html() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+    let site = TempDir::new().unwrap();
+
+    backup_command(&env, &output)
+        .args(["--token", "alice-token"])
+        .assert()
+        .success();
+
+    env.exsb()
+        .arg("export-html")
+        .arg(output.path())
+        .arg(site.path())
+        .assert()
+        .success();
+
+    site.child("index.html")
+        .assert(predicates::str::contains("<a href=\"rust/index.html\">rust</a>"));
+    site.child("rust/index.html")
+        .assert(predicates::str::contains("<span class=\"badge published\">published</span>"));
+    site.child("rust/poker/index.html")
+        .assert(predicates::str::contains("<dt>Mentoring</dt><dd>in progress</dd>"));
+}
+
+#[tokio::test]
+async fn test_backup_keep_iterations() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+    let old_file = output.child("python/leap/old.py");
+
+    backup_command(&env, &output)
+        .args(["--token", "bob-token"])
+        .assert()
+        .success();
+    old_file.write_str("print('old')\n").unwrap();
+
+    backup_command(&env, &output)
+        .args(["--token", "bob-token", "--force", "--keep-iterations"])
+        .assert()
+        .success();
+
+    old_file.assert(predicates::path::missing());
+    output
+        .child("python/leap/leap.py")
+        .assert(predicates::path::exists());
+    output
+        .child("python/leap/.exsb/iterations/1/old.py")
+        .assert("print('old')\n");
+    output
+        .child("python/leap/.exsb/iterations/1/leap.py")
+        .assert(predicates::path::exists());
+}
+
+#[tokio::test]
+async fn test_search() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+
+    backup_command(&env, &output)
+        .args(["--token", "alice-token"])
+        .assert()
+        .success();
+
+    env.exsb()
+        .args(["search", r"\bfn\b"])
+        .arg(output.path())
+        .args(["--status", "published", "--context", "0"])
+        .assert()
+        .success()
+        .stdout("rust/hello-world\nsrc/lib.rs:1:pub fn hello() -> &'static str {\n");
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use assert_cmd::Command;
+use common::TestEnv;
+use exsb::fake_server::FakeServer;
+
+fn stats_command(env: &TestEnv) -> Command {
+    let mut cmd = env.command("stats");
+    cmd.args(["--from-api", "--token", "alice-token"]);
+    cmd
+}
+
+/// Returns the number of requests listing solutions and how many of them were revalidations.
+fn solution_listings(server: &FakeServer) -> (usize, usize) {
+    let listings = server
+        .received_requests()
+        .into_iter()
+        .filter(|request| request.url.path() == "/solutions")
+        .collect::<Vec<_>>();
+    let revalidations = listings
+        .iter()
+        .filter(|request| request.headers.contains_key("if-none-match"))
+        .count();
+
+    (listings.len(), revalidations)
+}
+
+#[tokio::test]
+async fn test_cache() {
+    let env = TestEnv::start().await;
+
+    stats_command(&env).assert().success();
+    let (listings, _) = solution_listings(&env.server);
+    assert!(listings > 0);
+
+    stats_command(&env).assert().success();
+    assert_eq!((listings, 0), solution_listings(&env.server));
+
+    stats_command(&env)
+        .args(["--cache-ttl", "0s"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("rust"));
+    assert_eq!((listings * 2, listings), solution_listings(&env.server));
+
+    stats_command(&env)
+        .arg("--no-cache")
+        .assert()
+        .success();
+    assert_eq!((listings * 3, listings), solution_listings(&env.server));
+}
@@ -1,8 +1,34 @@
 use assert_cmd::{crate_name, Command};
 
 #[test]
-fn test_backup_basic() {
+fn test_help() {
+    let commands = [
+        "backup",
+        "restore",
+        #[cfg(feature = "keyring")]
+        "auth",
+        "stats",
+        "export-html",
+        "serve",
+        "search",
+        "diff",
+        "prune",
+        "daemon",
+        "watch",
+        "completions",
+        "man",
+    ];
+
+    for command in commands {
+        let mut cmd = Command::cargo_bin(crate_name!()).unwrap();
+
+        cmd.arg(command).arg("--help").assert().success();
+    }
+}
+
+#[test]
+fn test_completions_basic() {
     let mut cmd = Command::cargo_bin(crate_name!()).unwrap();
 
-    cmd.arg("backup").arg("--help").assert().success();
+    cmd.arg("completions").arg("bash").assert().success();
 }
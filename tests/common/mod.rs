@@ -0,0 +1,59 @@
+//! Helpers shared by integration tests.
+
+// Each test crate only uses some of the helpers.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use assert_cmd::{crate_name, Command};
+use assert_fs::TempDir;
+use exsb::fake_server::FakeServer;
+
+/// Returns the path of the fixtures served by the [`FakeServer`] by default.
+pub fn fixtures_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/accounts")
+}
+
+/// Returns a command running `exsb`, isolated from the user's environment: the Exercism API
+/// token is not inherited and the cache is stored in `cache_dir`.
+pub fn exsb_command(cache_dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin(crate_name!()).unwrap();
+    cmd.env("EXSB_CACHE_DIR", cache_dir.path())
+        .env_remove("EXERCISM_TOKEN");
+    cmd
+}
+
+/// Fake Exercism API server, along with the cache directory of the commands using it.
+pub struct TestEnv {
+    pub server: FakeServer,
+    pub cache_dir: TempDir,
+}
+
+impl TestEnv {
+    /// Starts a fake server serving the fixtures at [`fixtures_path`].
+    pub async fn start() -> Self {
+        Self::start_with_fixtures(&fixtures_path()).await
+    }
+
+    /// Starts a fake server serving the given fixtures.
+    pub async fn start_with_fixtures(fixtures_path: &Path) -> Self {
+        let server = FakeServer::start(fixtures_path).await.unwrap();
+        let cache_dir = TempDir::new().unwrap();
+
+        Self { server, cache_dir }
+    }
+
+    /// Returns a command running `exsb` (see [`exsb_command`]).
+    pub fn exsb(&self) -> Command {
+        exsb_command(&self.cache_dir)
+    }
+
+    /// Returns a command running the given `exsb` subcommand against the fake server.
+    pub fn command(&self, command: &str) -> Command {
+        let mut cmd = self.exsb();
+        cmd.arg(command)
+            .arg("--api-base-url")
+            .arg(self.server.uri());
+        cmd
+    }
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use common::{exsb_command, TestEnv};
+
+#[tokio::test]
+async fn test_completion_values() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+
+    // An invalid list (e.g. left by an older version) is ignored, then replaced.
+    env.cache_dir
+        .child("known-solutions.json")
+        .write_str("[{\"track\": ")
+        .unwrap();
+    env.exsb()
+        .args(["completions", "--values", "track"])
+        .assert()
+        .success()
+        .stdout("");
+
+    env.command("backup")
+        .arg(output.path())
+        .args(["--token", "alice-token", "--track", "rust"])
+        .assert()
+        .success();
+
+    // All solutions seen are known, even those that were not backed up.
+    env.exsb()
+        .args(["completions", "--values", "track"])
+        .assert()
+        .success()
+        .stdout("elixir\nrust\n");
+    env.exsb()
+        .args(["completions", "--values", "exercise"])
+        .assert()
+        .success()
+        .stdout("hello-world\npoker\ntwo-fer\n");
+}
+
+#[test]
+fn test_man() {
+    let cache_dir = TempDir::new().unwrap();
+    let output = TempDir::new().unwrap();
+
+    exsb_command(&cache_dir)
+        .arg("man")
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with(".ie"))
+        .stdout(predicates::str::contains(".TH exsb 1"));
+
+    exsb_command(&cache_dir)
+        .args(["man", "--output"])
+        .arg(output.path())
+        .assert()
+        .success();
+    output
+        .child("exsb-backup.1")
+        .assert(predicates::str::contains("exsb\\-backup"));
+    output
+        .child("exsb-help.1")
+        .assert(predicates::path::missing());
+}
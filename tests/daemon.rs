@@ -0,0 +1,57 @@
+mod common;
+
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use common::TestEnv;
+use serde_json::Value;
+
+fn daemon_command(env: &TestEnv, output: &TempDir) -> Command {
+    let mut cmd = env.command("daemon");
+    cmd.arg(output.path());
+    cmd
+}
+
+fn read_status(output: &TempDir) -> Value {
+    let status = std::fs::read(output.child(".exsb/daemon-status.json").path()).unwrap();
+    serde_json::from_slice(&status).unwrap()
+}
+
+#[tokio::test]
+async fn test_daemon() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+    let log_file = output.child("daemon.log");
+
+    daemon_command(&env, &output)
+        .args(["--token", "alice-token", "--interval", "100ms", "--max-runs", "2", "--log-file"])
+        .arg(log_file.path())
+        .assert()
+        .success();
+
+    output
+        .child("rust/poker/src/lib.rs")
+        .assert(predicates::path::exists());
+    log_file.assert(predicates::str::contains("Exercism solutions backup complete"));
+
+    let status = read_status(&output);
+    assert_eq!(2, status["runs"]);
+    assert_eq!(0, status["failures"]);
+    assert_eq!(true, status["last_run"]["success"]);
+    assert_eq!(Value::Null, status["next_run"]);
+
+    // Status is kept between executions of the daemon.
+    daemon_command(&env, &output)
+        .args(["--token", "invalid-token", "--max-runs", "1"])
+        .assert()
+        .success();
+
+    let status = read_status(&output);
+    assert_eq!(3, status["runs"]);
+    assert_eq!(1, status["failures"]);
+    assert_eq!(false, status["last_run"]["success"]);
+    assert!(status["last_run"]["error"]
+        .as_str()
+        .unwrap()
+        .contains("invalid"));
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use common::TestEnv;
+
+async fn backup(env: &TestEnv, output: &TempDir, extra_args: &[&str]) {
+    env.command("backup")
+        .arg(output.path())
+        .args(["--token", "bob-token"])
+        .args(extra_args)
+        .assert()
+        .success();
+}
+
+#[tokio::test]
+async fn test_diff_with_remote() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+
+    backup(&env, &output, &[]).await;
+    output
+        .child("python/leap/leap.py")
+        .write_str("def leap_year(year):\n    return False\n")
+        .unwrap();
+    output
+        .child("python/leap/notes.txt")
+        .write_str("todo\n")
+        .unwrap();
+
+    env.command("diff")
+        .arg(output.path())
+        .args(["python", "leap", "--token", "bob-token"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("--- a/leap.py\n+++ b/leap.py\n"))
+        .stdout(predicates::str::contains("-    return False\n"))
+        .stdout(predicates::str::contains("--- a/notes.txt\n+++ /dev/null\n"));
+
+    // Existing solutions are not downloaded again without --force.
+    backup(&env, &output, &[]).await;
+    output
+        .child("python/leap/notes.txt")
+        .assert(predicates::path::exists());
+}
+
+#[tokio::test]
+async fn test_diff_iterations() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+
+    backup(&env, &output, &[]).await;
+    output
+        .child("python/leap/old.py")
+        .write_str("print('old')\n")
+        .unwrap();
+    backup(&env, &output, &["--force", "--keep-iterations"]).await;
+
+    env.command("diff")
+        .arg(output.path())
+        .args(["python", "leap", "--iterations", "1", "latest"])
+        .assert()
+        .success()
+        .stdout("--- a/old.py\n+++ /dev/null\n@@ -1 +0,0 @@\n-print('old')\n");
+
+    env.command("diff")
+        .arg(output.path())
+        .args(["python", "leap", "--iterations", "2", "latest"])
+        .assert()
+        .failure();
+}
+
+#[tokio::test]
+async fn test_diff_without_metadata() {
+    let fixtures = TempDir::new().unwrap();
+    let account = fixtures.child("carol");
+    account.child("token").write_str("carol-token").unwrap();
+    for i in 0..exsb::fake_server::DEFAULT_PER_PAGE * 3 {
+        account
+            .child(format!("solutions/rust/exercise-{i:03}/files/src/lib.rs"))
+            .write_str("pub fn answer() -> i32 { 42 }\n")
+            .unwrap();
+    }
+    let env = TestEnv::start_with_fixtures(fixtures.path()).await;
+    let output = TempDir::new().unwrap();
+
+    env.command("backup")
+        .arg(output.path())
+        .args(["--token", "carol-token", "--exercise", "exercise-000"])
+        .assert()
+        .success();
+    std::fs::remove_dir_all(output.child("rust/exercise-000/.exsb").path()).unwrap();
+    let solution_listings = || {
+        env.server
+            .received_requests()
+            .into_iter()
+            .filter(|request| request.url.path() == "/solutions")
+            .count()
+    };
+    let listings_before_diff = solution_listings();
+
+    env.command("diff")
+        .arg(output.path())
+        .args(["rust", "exercise-000", "--token", "carol-token", "--no-cache"])
+        .assert()
+        .success()
+        .stdout("");
+
+    // The solution is in the first page, so other pages are not fetched.
+    assert_eq!(listings_before_diff + 1, solution_listings());
+}
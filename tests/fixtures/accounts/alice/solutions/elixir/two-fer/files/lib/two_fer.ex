@@ -0,0 +1,4 @@
+defmodule TwoFer do
+  @spec two_fer(String.t()) :: String.t()
+  def two_fer(name \\ "you"), do: "One for #{name}, one for me."
+end
@@ -0,0 +1,3 @@
+pub fn winning_hands<'a>(hands: &[&'a str]) -> Vec<&'a str> {
+    hands.to_vec()
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use common::TestEnv;
+
+fn exsb_command(env: &TestEnv, command: &str, output: &TempDir) -> Command {
+    let mut cmd = env.command(command);
+    cmd.arg(output.path()).args(["--token", "alice-token"]);
+    cmd
+}
+
+async fn backup_with_orphans(env: &TestEnv, output: &TempDir) {
+    exsb_command(env, "backup", output).assert().success();
+    output
+        .child("rust/old-exercise/src/lib.rs")
+        .write_str("")
+        .unwrap();
+    output
+        .child("haskell/leap/src/Leap.hs")
+        .write_str("")
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_prune() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+    backup_with_orphans(&env, &output).await;
+
+    exsb_command(&env, "prune", &output)
+        .arg("--dry-run")
+        .assert()
+        .success();
+    output
+        .child("rust/old-exercise")
+        .assert(predicates::path::exists());
+
+    exsb_command(&env, "prune", &output)
+        .args(["--track", "rust"])
+        .assert()
+        .success();
+    output
+        .child("rust/old-exercise")
+        .assert(predicates::path::missing());
+    output.child("haskell").assert(predicates::path::missing());
+    output.child("elixir").assert(predicates::path::missing());
+    output
+        .child("rust/poker/src/lib.rs")
+        .assert(predicates::path::exists());
+}
+
+#[tokio::test]
+async fn test_prune_with_quarantine() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+    let quarantine = TempDir::new().unwrap();
+    backup_with_orphans(&env, &output).await;
+
+    exsb_command(&env, "prune", &output)
+        .arg("--quarantine")
+        .arg(quarantine.path())
+        .assert()
+        .success();
+    output
+        .child("rust/old-exercise")
+        .assert(predicates::path::missing());
+    quarantine
+        .child("rust/old-exercise/src/lib.rs")
+        .assert(predicates::path::exists());
+    quarantine
+        .child("haskell/leap/src/Leap.hs")
+        .assert(predicates::path::exists());
+    output
+        .child("elixir/two-fer")
+        .assert(predicates::path::exists());
+}
+
+#[tokio::test]
+async fn test_prune_multiple_accounts() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+    let config = TempDir::new().unwrap();
+    let accounts_file = config.child("accounts.txt");
+    accounts_file
+        .write_str("alice = alice-token\nbob = bob-token\n")
+        .unwrap();
+    env.command("backup")
+        .arg(output.path())
+        .arg("--accounts-file")
+        .arg(accounts_file.path())
+        .assert()
+        .success();
+    output
+        .child("alice/rust/old-exercise/src/lib.rs")
+        .write_str("")
+        .unwrap();
+
+    env.command("prune")
+        .arg(output.path())
+        .arg("--accounts-file")
+        .arg(accounts_file.path())
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("Would prune 1 orphaned"));
+    output
+        .child("alice/rust/old-exercise")
+        .assert(predicates::path::exists());
+
+    env.command("prune")
+        .arg(output.path())
+        .arg("--accounts-file")
+        .arg(accounts_file.path())
+        .assert()
+        .success();
+    output
+        .child("alice/rust/old-exercise")
+        .assert(predicates::path::missing());
+    output
+        .child("alice/rust/poker/src/lib.rs")
+        .assert(predicates::path::exists());
+    output
+        .child("bob/python/leap/leap.py")
+        .assert(predicates::path::exists());
+}
+
+#[tokio::test]
+async fn test_prune_without_solutions() {
+    let fixtures = TempDir::new().unwrap();
+    fixtures
+        .child("carol/token")
+        .write_str("carol-token")
+        .unwrap();
+    let env = TestEnv::start_with_fixtures(fixtures.path()).await;
+    let output = TempDir::new().unwrap();
+    output.child("rust/poker/src/lib.rs").write_str("").unwrap();
+
+    env.command("prune")
+        .arg(output.path())
+        .args(["--token", "carol-token"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("refusing to prune"));
+    output
+        .child("rust/poker/src/lib.rs")
+        .assert(predicates::path::exists());
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use std::net::{Ipv4Addr, TcpListener};
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+use assert_cmd::cargo::cargo_bin;
+use assert_cmd::crate_name;
+use assert_fs::TempDir;
+use common::TestEnv;
+use reqwest::StatusCode;
+
+/// `exsb serve` process, killed when dropped.
+struct ServeProcess {
+    child: Child,
+    port: u16,
+}
+
+impl ServeProcess {
+    async fn start(env: &TestEnv, backup: &TempDir) -> Self {
+        let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let child = Command::new(cargo_bin(crate_name!()))
+            .env("EXSB_CACHE_DIR", env.cache_dir.path())
+            .arg("serve")
+            .arg(backup.path())
+            .args(["--port", &port.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        let process = Self { child, port };
+
+        for _ in 0..100 {
+            if reqwest::get(process.url("")).await.is_ok() {
+                return process;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        panic!("serve command did not start listening on port {port}");
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("http://localhost:{}/{path}", self.port)
+    }
+
+    async fn get(&self, path: &str) -> (StatusCode, String) {
+        let response = reqwest::get(self.url(path)).await.unwrap();
+        (response.status(), response.text().await.unwrap())
+    }
+}
+
+impl Drop for ServeProcess {
+    fn drop(&mut self) {
+        self.child.kill().ok();
+        self.child.wait().ok();
+    }
+}
+
+#[tokio::test]
+async fn test_serve() {
+    let env = TestEnv::start().await;
+    let backup = TempDir::new().unwrap();
+    env.command("backup")
+        .arg(backup.path())
+        .args(["--token", "alice-token"])
+        .assert()
+        .success();
+
+    let serve = ServeProcess::start(&env, &backup).await;
+
+    let (status, index) = serve.get("").await;
+    assert_eq!(StatusCode::OK, status);
+    assert!(index.contains("<a href=\"rust/index.html\">rust</a></td><td>2</td>"));
+    assert!(index.contains("action=\"search.html\""));
+
+    let (status, track) = serve.get("rust/").await;
+    assert_eq!(StatusCode::OK, status);
+    assert!(track.contains("<a href=\"poker/index.html\">poker</a>"));
+
+    let (status, solution) = serve.get("rust/poker/index.html").await;
+    assert_eq!(StatusCode::OK, status);
+    assert!(solution.contains("<h2>src/lib.rs</h2>"));
+
+    let (status, search) = serve.get("search.html?q=fn").await;
+    assert_eq!(StatusCode::OK, status);
+    assert!(search.contains("value=\"fn\""));
+    assert!(search.contains("<a href=\"rust/poker/index.html\">rust/poker</a>"));
+
+    let (status, _) = serve.get("rust/leap/index.html").await;
+    assert_eq!(StatusCode::NOT_FOUND, status);
+}
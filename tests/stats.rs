@@ -0,0 +1,57 @@
+mod common;
+
+use assert_cmd::Command;
+use assert_fs::TempDir;
+use common::TestEnv;
+use serde_json::Value;
+
+fn stats_json(cmd: &mut Command) -> Value {
+    let output = cmd.args(["--format", "json"]).assert().success();
+    serde_json::from_slice(&output.get_output().stdout).unwrap()
+}
+
+#[tokio::test]
+async fn test_stats_from_backup() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+
+    env.command("backup")
+        .arg(output.path())
+        .args(["--token", "alice-token"])
+        .assert()
+        .success();
+
+    let stats = stats_json(env.command("stats").arg(output.path()));
+    assert_eq!(3, stats["total"]["solutions"]);
+    assert_eq!(6, stats["total"]["iterations"]);
+    assert_eq!(
+        vec!["elixir", "rust"],
+        stats["tracks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|track| track["track"].as_str().unwrap())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(1, stats["tracks"][1]["submitted"]);
+    assert_eq!(1, stats["tracks"][1]["published"]);
+
+    env.command("stats")
+        .arg(output.path())
+        .args(["--format", "csv"])
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with(
+            "track,exercise,status,iterations,completed_at,files,lines,bytes\nelixir,two-fer,completed,1,",
+        ));
+}
+
+#[tokio::test]
+async fn test_stats_from_api() {
+    let env = TestEnv::start().await;
+
+    let stats =
+        stats_json(env.command("stats").args(["--from-api", "--token", "alice-token"]));
+    assert_eq!(3, stats["total"]["solutions"]);
+    assert_eq!(9, stats["total"]["lines"]);
+}
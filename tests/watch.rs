@@ -0,0 +1,80 @@
+mod common;
+
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use common::TestEnv;
+use serde_json::Value;
+
+fn exsb_command(env: &TestEnv, command: &str, output: &TempDir) -> Command {
+    let mut cmd = env.command(command);
+    cmd.arg(output.path()).args(["--token", "alice-token"]);
+    cmd
+}
+
+#[tokio::test]
+async fn test_watch() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+
+    exsb_command(&env, "backup", &output)
+        .args(["--track", "rust"])
+        .assert()
+        .success();
+    output.child("rust/hello-world/notes.txt").touch().unwrap();
+    output.child("rust/poker/notes.txt").touch().unwrap();
+
+    // Pretend a new iteration of poker was submitted since the backup.
+    let poker_metadata = output.child("rust/poker/.exsb/solution.json");
+    let mut metadata: Value =
+        serde_json::from_slice(&std::fs::read(poker_metadata.path()).unwrap()).unwrap();
+    metadata["num_iterations"] = 2.into();
+    poker_metadata
+        .write_str(&serde_json::to_string(&metadata).unwrap())
+        .unwrap();
+
+    exsb_command(&env, "watch", &output)
+        .args(["--max-polls", "1"])
+        .assert()
+        .success();
+
+    output
+        .child("elixir/two-fer/lib/two_fer.ex")
+        .assert(predicates::path::exists());
+    output
+        .child("rust/hello-world/notes.txt")
+        .assert(predicates::path::exists());
+    // Changed solutions are updated in place, without removing other files.
+    output
+        .child("rust/poker/notes.txt")
+        .assert(predicates::path::exists());
+    output
+        .child("rust/poker/src/lib.rs")
+        .assert(predicates::path::exists());
+    poker_metadata.assert(predicates::str::contains("\"num_iterations\": 3"));
+}
+
+#[tokio::test]
+async fn test_watch_multiple_accounts() {
+    let env = TestEnv::start().await;
+    let output = TempDir::new().unwrap();
+    let config = TempDir::new().unwrap();
+    let accounts_file = config.child("accounts.txt");
+    accounts_file
+        .write_str("alice = alice-token\nmallory = invalid-token\n")
+        .unwrap();
+
+    env.command("watch")
+        .arg(output.path())
+        .arg("--accounts-file")
+        .arg(accounts_file.path())
+        .args(["--max-polls", "1"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("1 account failed: "));
+
+    // Other accounts are backed up anyway.
+    output
+        .child("alice/rust/poker/src/lib.rs")
+        .assert(predicates::path::exists());
+}